@@ -0,0 +1,815 @@
+use apricot_client::client::{self, ApricotClient};
+use apricot_client::config::{ApricotConfig, PoolConfig};
+use apricot_client::consts;
+use apricot_client::decoder::InstructionDecodeError;
+use apricot_client::env::{ApricotEnv, Cluster};
+use apricot_client::health::HealthError;
+use apricot_client::liquidation;
+use apricot_client::lp::LpAction;
+use apricot_client::view::{AssetPoolView, UserInfoView};
+use clap::{Parser, Subcommand, ValueEnum};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// Command line client for Apricot. Token amounts are given in UI units and converted with the
+// pool's decimals, tokens are named as in the deployment's PoolRegistry or by pool id.
+
+#[derive(Parser)]
+#[command(name = "apricot", version, about = "Command line client for Apricot")]
+struct Cli {
+    /// Cluster to connect to
+    #[arg(long, value_enum, default_value = "mainnet", global = true)]
+    cluster: ClusterArg,
+    /// RPC URL, overrides the cluster's public node
+    #[arg(long, short = 'u', global = true)]
+    url: Option<String>,
+    /// processed, confirmed or finalized
+    #[arg(long, default_value = "confirmed", value_parser = parse_commitment, global = true)]
+    commitment: CommitmentConfig,
+    /// Keypair file of the wallet, ~/.config/solana/id.json by default
+    #[arg(long, short = 'k', global = true)]
+    keypair: Option<PathBuf>,
+    /// JSON or TOML description of the Apricot deployment, required off mainnet
+    #[arg(long, global = true)]
+    deployment: Option<PathBuf>,
+    /// Print the instructions instead of sending them
+    #[arg(long, global = true)]
+    dry_run: bool,
+    /// Wallet to build the instructions for with --dry-run, no keypair is read then
+    #[arg(
+        long = "wallet",
+        value_name = "PUBKEY",
+        global = true,
+        requires = "dry_run"
+    )]
+    dry_run_wallet: Option<Pubkey>,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum ClusterArg {
+    Mainnet,
+    Devnet,
+    Localnet,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deposits, borrows and borrow power of a wallet, the keypair's by default
+    User {
+        wallet: Option<Pubkey>,
+        /// USD price of a token as TOKEN=PRICE, borrow power is shown once every pool of the
+        /// wallet has one
+        #[arg(long = "price", value_parser = parse_price)]
+        prices: Vec<(String, f64)>,
+    },
+    /// Totals and rates of a pool
+    Pool { token: String },
+    /// Deposits from the wallet's token account, registering the user when needed
+    Deposit {
+        token: String,
+        #[arg(value_parser = parse_amount)]
+        amount: f64,
+    },
+    /// Withdraws an amount or all of a deposit
+    Withdraw { token: String, amount: Amount },
+    /// Borrows into the wallet's token account, creating it when needed
+    Borrow {
+        token: String,
+        #[arg(value_parser = parse_amount)]
+        amount: f64,
+    },
+    /// Repays an amount or all of a borrow
+    Repay { token: String, amount: Amount },
+    /// Uses a deposit as collateral or stops doing so
+    Collateral { token: String, state: Toggle },
+    /// Accrues interest and rewards of the wallet up to now
+    RefreshUser,
+    /// Liquidates the most profitable part of an unhealthy wallet's borrows
+    Liquidate {
+        wallet: Pubkey,
+        /// Fraction kept off the repaid and received amounts, room for price moves
+        #[arg(long, default_value_t = 0.01)]
+        margin: f64,
+        /// USD price of a token as TOKEN=PRICE, required for every pool of the wallet
+        #[arg(long = "price", value_parser = parse_price)]
+        prices: Vec<(String, f64)>,
+    },
+    /// Swaps one asset of the portfolio into another through a DEX, borrowing what's missing
+    Swap {
+        sell: String,
+        buy: String,
+        #[arg(long, value_parser = parse_amount)]
+        sell_amount: f64,
+        #[arg(long, value_parser = parse_amount)]
+        buy_amount: f64,
+        /// buy_amount is exact and sell_amount the most to sell, otherwise sell_amount is exact
+        /// and buy_amount the least to receive
+        #[arg(long)]
+        exact_buy: bool,
+        /// LP token whose DEX pool swaps sell into buy, such as SOL_USDC_ORCA
+        #[arg(long)]
+        via: String,
+    },
+    /// LP positions of the wallet's portfolio
+    Lp {
+        /// Account of the LP op check and endcheck around the operation, in the order the
+        /// contract expects, PUBKEY or PUBKEY:w when writable
+        #[arg(long = "check-account", value_parser = parse_account_meta)]
+        check_accounts: Vec<AccountMeta>,
+        #[command(subcommand)]
+        action: LpCommand,
+    },
+    /// APT liquidity mining rewards
+    Rewards {
+        #[command(subcommand)]
+        action: RewardsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum LpCommand {
+    /// Adds deposits of both legs to the LP pool
+    Create {
+        lp_token: String,
+        #[arg(value_parser = parse_amount)]
+        left_amount: f64,
+        #[arg(value_parser = parse_amount)]
+        right_amount: f64,
+        #[arg(long, default_value_t = 0.0, value_parser = parse_min_amount)]
+        min_lp_amount: f64,
+    },
+    /// Turns LP tokens back into deposits of both legs
+    Redeem {
+        lp_token: String,
+        #[arg(value_parser = parse_amount)]
+        lp_amount: f64,
+        #[arg(long, default_value_t = 0.0, value_parser = parse_min_amount)]
+        min_left_amount: f64,
+        #[arg(long, default_value_t = 0.0, value_parser = parse_min_amount)]
+        min_right_amount: f64,
+    },
+}
+
+#[derive(Subcommand)]
+enum RewardsCommand {
+    /// Moves vested APT to the claimable balance
+    MakeClaimable,
+    /// Sends claimable APT to the wallet's token account
+    Claim,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Amount {
+    All,
+    Ui(f64),
+}
+
+impl FromStr for Amount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "all" => Ok(Amount::All),
+            _ => parse_amount(s)
+                .map(Amount::Ui)
+                .map_err(|_| format!("expected a positive number or \"all\", got {}", s)),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+enum Toggle {
+    On,
+    Off,
+}
+
+fn parse_commitment(s: &str) -> Result<CommitmentConfig, String> {
+    CommitmentConfig::from_str(s).map_err(|err| err.to_string())
+}
+
+fn parse_price(s: &str) -> Result<(String, f64), String> {
+    let (token, price) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected TOKEN=PRICE, got {}", s))?;
+    let price = price
+        .parse::<f64>()
+        .map_err(|err| format!("{}: {}", price, err))?;
+    Ok((token.to_string(), price))
+}
+
+// amounts sent or received, `as u64` would turn NaN and negative amounts into 0
+fn parse_amount(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount > 0.0 => Ok(amount),
+        _ => Err(format!("expected a positive number, got {}", s)),
+    }
+}
+
+// slippage limits, 0 accepts any amount
+fn parse_min_amount(s: &str) -> Result<f64, String> {
+    match s.parse::<f64>() {
+        Ok(amount) if amount.is_finite() && amount >= 0.0 => Ok(amount),
+        _ => Err(format!("expected a number of at least 0, got {}", s)),
+    }
+}
+
+fn parse_account_meta(s: &str) -> Result<AccountMeta, String> {
+    let (key, writable) = match s.strip_suffix(":w") {
+        Some(key) => (key, true),
+        None => (s, false),
+    };
+    let key = Pubkey::from_str(key).map_err(|err| format!("{}: {}", key, err))?;
+    Ok(match writable {
+        true => AccountMeta::new(key, false),
+        false => AccountMeta::new_readonly(key, false),
+    })
+}
+
+#[derive(Debug)]
+enum CliError {
+    // devnet and localnet have no known deployment
+    NoDeployment(ClusterArg),
+    Keypair {
+        path: PathBuf,
+        reason: String,
+    },
+    UnknownToken(String),
+    NotLpPool(String),
+    // the DEX pool of an LP token doesn't swap between the two tokens
+    NoSwap {
+        via: String,
+        sell: String,
+        buy: String,
+    },
+    NotLiquidatable(Pubkey),
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CliError::NoDeployment(cluster) => write!(
+                f,
+                "no Apricot deployment known on {:?}, pass --deployment",
+                cluster
+            ),
+            CliError::Keypair { path, reason } => {
+                write!(f, "cannot read keypair {}: {}", path.display(), reason)
+            }
+            CliError::UnknownToken(token) => write!(f, "unknown token {}", token),
+            CliError::NotLpPool(token) => write!(f, "{} is not an LP pool", token),
+            CliError::NoSwap { via, sell, buy } => {
+                write!(f, "{} can't swap {} into {}", via, sell, buy)
+            }
+            CliError::NotLiquidatable(wallet) => write!(f, "{} can't be liquidated", wallet),
+        }
+    }
+}
+
+impl Error for CliError {}
+
+type CliResult<T> = Result<T, Box<dyn Error>>;
+
+struct Context {
+    client: ApricotClient,
+    keypair_path: PathBuf,
+    dry_run: bool,
+    wallet: Option<Pubkey>,
+}
+
+impl Context {
+    fn new(cli: &Cli) -> CliResult<Self> {
+        let config = match &cli.deployment {
+            Some(path) => ApricotConfig::from_file(path)?,
+            None if cli.cluster == ClusterArg::Mainnet => ApricotConfig::mainnet(),
+            None => return Err(CliError::NoDeployment(cli.cluster).into()),
+        };
+        let cluster = match (&cli.url, cli.cluster) {
+            (Some(url), _) => Cluster::Custom(url.clone()),
+            (None, ClusterArg::Mainnet) => Cluster::Mainnet,
+            (None, ClusterArg::Devnet) => Cluster::Devnet,
+            (None, ClusterArg::Localnet) => Cluster::Localnet,
+        };
+        let rpc = RpcClient::new_with_commitment(cluster.rpc_url().to_string(), cli.commitment);
+        let keypair_path = match &cli.keypair {
+            Some(path) => path.clone(),
+            None => default_keypair_path(),
+        };
+        Ok(Context {
+            client: ApricotClient::with_rpc(rpc, ApricotEnv::new(cluster, config)),
+            keypair_path,
+            dry_run: cli.dry_run,
+            wallet: cli.dry_run_wallet,
+        })
+    }
+
+    fn env(&self) -> &ApricotEnv {
+        self.client.env()
+    }
+
+    fn keypair(&self) -> CliResult<Keypair> {
+        read_keypair_file(&self.keypair_path).map_err(|err| {
+            CliError::Keypair {
+                path: self.keypair_path.clone(),
+                reason: err.to_string(),
+            }
+            .into()
+        })
+    }
+
+    // --wallet of a dry run, the keypair's otherwise
+    fn wallet(&self) -> CliResult<Pubkey> {
+        match self.wallet {
+            Some(wallet) => Ok(wallet),
+            None => Ok(self.keypair()?.pubkey()),
+        }
+    }
+
+    fn pool(&self, token: &str) -> CliResult<&PoolConfig> {
+        let pools = &self.env().config.pools;
+        let pool = match token.parse::<u8>() {
+            Ok(pool_id) => pools.by_pool_id(pool_id),
+            Err(_) => pools.by_name(token),
+        };
+        pool.ok_or_else(|| CliError::UnknownToken(token.to_string()).into())
+    }
+
+    fn lp_pool(&self, token: &str) -> CliResult<&PoolConfig> {
+        let pool = self.pool(token)?;
+        match pool.is_lp() {
+            true => Ok(pool),
+            false => Err(CliError::NotLpPool(token.to_string()).into()),
+        }
+    }
+
+    // pool_id to USD price of --price arguments
+    fn prices(&self, prices: &[(String, f64)]) -> CliResult<BTreeMap<u8, f64>> {
+        prices
+            .iter()
+            .map(|(token, price)| Ok((self.pool(token)?.pool_id, *price)))
+            .collect()
+    }
+
+    // rounded to the nearest native unit, 0.29 USDC is 290_000 and not 289_999
+    fn native_amount(&self, pool_id: u8, ui_amount: f64) -> CliResult<u64> {
+        let pool = self
+            .env()
+            .config
+            .pools
+            .by_pool_id(pool_id)
+            .ok_or_else(|| CliError::UnknownToken(pool_id.to_string()))?;
+        Ok((ui_amount * pool.decimal_multiplier() as f64).round() as u64)
+    }
+
+    // wallet's associated token account of pool's mint, created first when it doesn't exist
+    async fn token_account(
+        &self,
+        wallet: &Pubkey,
+        mint: &Pubkey,
+        ixs: &mut Vec<Instruction>,
+    ) -> CliResult<Pubkey> {
+        let user_spl = consts::get_associated_token_k(wallet, mint);
+        if self.client.account(&user_spl).await?.is_none() {
+            ixs.push(client::create_associated_token_account(
+                wallet, wallet, mint,
+            ));
+        }
+        Ok(user_spl)
+    }
+
+    async fn send(&self, ixs: &[Instruction]) -> CliResult<()> {
+        if self.dry_run {
+            for ix in ixs {
+                print_instruction(self.env(), ix);
+            }
+            return Ok(());
+        }
+        let signature = self.client.send(ixs, &self.keypair()?).await?;
+        println!("Signature: {}", signature);
+        Ok(())
+    }
+}
+
+fn default_keypair_path() -> PathBuf {
+    let home = std::env::var_os("HOME").unwrap_or_default();
+    PathBuf::from(home).join(".config/solana/id.json")
+}
+
+fn print_instruction(env: &ApricotEnv, ix: &Instruction) {
+    match env.decode_instruction(ix) {
+        Ok(decoded) => println!("{:?}", decoded),
+        Err(InstructionDecodeError::WrongProgram(program_id)) => {
+            println!("{} with {} accounts", program_id, ix.accounts.len())
+        }
+        Err(err) => println!("undecodable Apricot instruction: {}", err),
+    }
+}
+
+async fn run(cli: Cli) -> CliResult<()> {
+    let ctx = Context::new(&cli)?;
+    match cli.command {
+        Command::User { wallet, prices } => {
+            let wallet = match wallet {
+                Some(wallet) => wallet,
+                None => ctx.wallet()?,
+            };
+            show_user(&ctx, &wallet, &ctx.prices(&prices)?).await
+        }
+        Command::Pool { token } => {
+            let config = ctx.pool(&token)?;
+            let pool_id = config.pool_id;
+            let pool = AssetPoolView::new(&ctx.client.asset_pool(pool_id).await?, config.decimals);
+            println!("AssetPool {}: {}", pool_id, ctx.env().asset_pool_k(pool_id));
+            println!(
+                "deposits {:.6}, borrows {:.6}, available {:.6}",
+                pool.deposit_amount(),
+                pool.borrow_amount(),
+                pool.available_amount()
+            );
+            println!(
+                "utilization {:.2}%, deposit APY {:.2}%, borrow APY {:.2}%",
+                pool.utilization_rate() * 100.0,
+                pool.deposit_apy() * 100.0,
+                pool.borrow_apy() * 100.0
+            );
+            Ok(())
+        }
+        Command::Deposit { token, amount } => {
+            let wallet = ctx.wallet()?;
+            let pool_id = ctx.pool(&token)?.pool_id;
+            let amount = ctx.native_amount(pool_id, amount)?;
+            let ixs = ctx
+                .client
+                .deposit_instructions(&wallet, pool_id, amount)
+                .await?;
+            ctx.send(&ixs).await
+        }
+        Command::Withdraw { token, amount } => {
+            let wallet = ctx.wallet()?;
+            let pool_id = ctx.pool(&token)?.pool_id;
+            let (withdraw_all, amount) = match amount {
+                Amount::All => (true, 0),
+                Amount::Ui(amount) => (false, ctx.native_amount(pool_id, amount)?),
+            };
+            let ixs = ctx
+                .client
+                .withdraw_instructions(&wallet, pool_id, withdraw_all, amount)
+                .await?;
+            ctx.send(&ixs).await
+        }
+        Command::Borrow { token, amount } => {
+            let wallet = ctx.wallet()?;
+            let pool = ctx.pool(&token)?;
+            let mut ixs = vec![];
+            let user_spl = ctx.token_account(&wallet, &pool.mint, &mut ixs).await?;
+            ixs.push(ctx.env().borrow(
+                &wallet,
+                &user_spl,
+                ctx.native_amount(pool.pool_id, amount)?,
+                pool.pool_id,
+            ));
+            ctx.send(&ixs).await
+        }
+        Command::Repay { token, amount } => {
+            let wallet = ctx.wallet()?;
+            let pool = ctx.pool(&token)?;
+            let user_spl = consts::get_associated_token_k(&wallet, &pool.mint);
+            let (repay_all, amount) = match amount {
+                Amount::All => (true, 0),
+                Amount::Ui(amount) => (false, ctx.native_amount(pool.pool_id, amount)?),
+            };
+            let ix = ctx
+                .env()
+                .repay(&wallet, &user_spl, repay_all, amount, pool.pool_id);
+            ctx.send(&[ix]).await
+        }
+        Command::Collateral { token, state } => {
+            let wallet = ctx.wallet()?;
+            let pool_id = ctx.pool(&token)?.pool_id;
+            let ix = ctx
+                .env()
+                .update_user_asset_config(&wallet, state == Toggle::On, pool_id);
+            ctx.send(&[ix]).await
+        }
+        Command::RefreshUser => {
+            let wallet = ctx.wallet()?;
+            let ix = ctx.env().refresh_user(&wallet);
+            ctx.send(&[ix]).await
+        }
+        Command::Liquidate {
+            wallet,
+            margin,
+            prices,
+        } => liquidate(&ctx, &wallet, margin, &ctx.prices(&prices)?).await,
+        Command::Swap {
+            sell,
+            buy,
+            sell_amount,
+            buy_amount,
+            exact_buy,
+            via,
+        } => {
+            let wallet = ctx.wallet()?;
+            let sell_pool_id = ctx.pool(&sell)?.pool_id;
+            let buy_pool_id = ctx.pool(&buy)?.pool_id;
+            let swap_accounts = ctx
+                .env()
+                .config
+                .pools
+                .swap_accounts(ctx.pool(&via)?.pool_id, sell_pool_id, buy_pool_id)
+                .ok_or(CliError::NoSwap { via, sell, buy })?;
+            let ix = ctx.env().margin_swap(
+                &wallet,
+                exact_buy,
+                sell_pool_id,
+                ctx.native_amount(sell_pool_id, sell_amount)?,
+                buy_pool_id,
+                ctx.native_amount(buy_pool_id, buy_amount)?,
+                &swap_accounts,
+            );
+            ctx.send(&[ix]).await
+        }
+        Command::Lp {
+            check_accounts,
+            action,
+        } => {
+            let wallet = ctx.wallet()?;
+            let action = lp_action(&ctx, action)?;
+            let ixs = ctx
+                .env()
+                .build_lp_ops(&wallet, &check_accounts, &[action])?;
+            ctx.send(&ixs).await
+        }
+        Command::Rewards { action } => {
+            let wallet = ctx.wallet()?;
+            let mut ixs = vec![];
+            match action {
+                RewardsCommand::MakeClaimable => {
+                    ixs.push(ctx.env().make_lm_reward_claimable(&wallet))
+                }
+                RewardsCommand::Claim => {
+                    let apt_mint = ctx.pool("APT")?.mint;
+                    let user_apt_spl = ctx.token_account(&wallet, &apt_mint, &mut ixs).await?;
+                    ixs.push(ctx.env().claim_apt_lm_reward(&wallet, &user_apt_spl));
+                }
+            }
+            ctx.send(&ixs).await
+        }
+    }
+}
+
+fn lp_action(ctx: &Context, command: LpCommand) -> CliResult<LpAction> {
+    Ok(match command {
+        LpCommand::Create {
+            lp_token,
+            left_amount,
+            right_amount,
+            min_lp_amount,
+        } => {
+            let pool = ctx.lp_pool(&lp_token)?;
+            let (left, right) = pool.lp_legs().unwrap_or_default();
+            LpAction::Create {
+                lp_pool_id: pool.pool_id,
+                left_amount: ctx.native_amount(left, left_amount)?,
+                right_amount: ctx.native_amount(right, right_amount)?,
+                min_lp_amount: ctx.native_amount(pool.pool_id, min_lp_amount)?,
+            }
+        }
+        LpCommand::Redeem {
+            lp_token,
+            lp_amount,
+            min_left_amount,
+            min_right_amount,
+        } => {
+            let pool = ctx.lp_pool(&lp_token)?;
+            let (left, right) = pool.lp_legs().unwrap_or_default();
+            LpAction::Redeem {
+                lp_pool_id: pool.pool_id,
+                lp_amount: ctx.native_amount(pool.pool_id, lp_amount)?,
+                min_left_amount: ctx.native_amount(left, min_left_amount)?,
+                min_right_amount: ctx.native_amount(right, min_right_amount)?,
+            }
+        }
+    })
+}
+
+async fn show_user(ctx: &Context, wallet: &Pubkey, prices: &BTreeMap<u8, f64>) -> CliResult<()> {
+    let snapshot = ctx.client.portfolio_snapshot(wallet).await?;
+    println!(
+        "UserInfo: {} at slot {}",
+        ctx.env().user_info_k(wallet),
+        snapshot.slot
+    );
+    let user_info = match &snapshot.user_info {
+        Some(user_info) => UserInfoView::from(user_info),
+        None => {
+            println!("no deposits or borrows");
+            return Ok(());
+        }
+    };
+    let pools = snapshot.pool_views(&ctx.env().config.pools);
+    for asset in user_info.assets() {
+        let pool = match pools.iter().find(|pool| pool.pool_id() == asset.pool_id()) {
+            Some(pool) => pool,
+            None => continue,
+        };
+        println!(
+            "{:>12}  deposit {:.6}{}  borrow {:.6}",
+            pool.token_name(),
+            asset.deposit_amount(pool),
+            if asset.use_as_collateral() {
+                " (collateral)"
+            } else {
+                ""
+            },
+            asset.borrow_amount(pool)
+        );
+    }
+    let info = match snapshot.borrow_power_with(&ctx.env().config.pools, |pool_id| {
+        prices.get(&pool_id).copied()
+    }) {
+        Ok(info) => info,
+        Err(HealthError::MissingPrice { .. }) => {
+            println!("pass --price TOKEN=PRICE for each pool to see the borrow power");
+            return Ok(());
+        }
+        Err(err) => return Err(err.into()),
+    };
+    if let Some(info) = info {
+        println!(
+            "collateral ${:.2}, borrow limit ${:.2}, borrowed ${:.2}, ratio {:.2}%{}",
+            info.total_collateral,
+            info.max_borrow_allowed,
+            info.total_borrow,
+            info.collateral_ratio * 100.0,
+            if info.is_liquidatable() {
+                ", liquidatable"
+            } else {
+                ""
+            }
+        );
+    }
+    Ok(())
+}
+
+async fn liquidate(
+    ctx: &Context,
+    wallet: &Pubkey,
+    margin: f64,
+    prices: &BTreeMap<u8, f64>,
+) -> CliResult<()> {
+    let liquidator = ctx.wallet()?;
+    let snapshot = ctx.client.portfolio_snapshot(wallet).await?;
+    let user_info = match &snapshot.user_info {
+        Some(user_info) => UserInfoView::from(user_info),
+        None => return Err(CliError::NotLiquidatable(*wallet).into()),
+    };
+    let pools = snapshot.pool_views(&ctx.env().config.pools);
+    let plan = liquidation::plan_liquidation(
+        &user_info,
+        &pools,
+        |pool_id| prices.get(&pool_id).copied(),
+        &ctx.env().config.pools,
+        margin,
+    )?
+    .ok_or(CliError::NotLiquidatable(*wallet))?;
+    println!(
+        "repaying ${:.2} of pool {} for ${:.2} of pool {}",
+        plan.repaid_value, plan.borrowed_pool_id, plan.collateral_value, plan.collateral_pool_id
+    );
+
+    let mut ixs = vec![];
+    ctx.token_account(&liquidator, &plan.collateral_mint, &mut ixs)
+        .await?;
+    ixs.push(plan.instruction(ctx.env(), wallet, &liquidator));
+    ctx.send(&ixs).await
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(err) = run(Cli::parse()).await {
+        eprintln!("error: {}", err);
+        std::process::exit(1);
+    }
+}
+
+#[cfg(test)]
+pub mod main_test {
+    use super::*;
+    use clap::CommandFactory;
+
+    #[test]
+    fn test_parse_args() {
+        Cli::command().debug_assert();
+
+        let cli = Cli::try_parse_from([
+            "apricot",
+            "--commitment",
+            "finalized",
+            "withdraw",
+            "USDC",
+            "all",
+        ])
+        .unwrap();
+        assert_eq!(cli.commitment, CommitmentConfig::finalized());
+        assert!(matches!(
+            cli.command,
+            Command::Withdraw {
+                amount: Amount::All,
+                ..
+            }
+        ));
+
+        let cli = Cli::try_parse_from([
+            "apricot",
+            "swap",
+            "SOL",
+            "USDC",
+            "--sell-amount=1.5",
+            "--buy-amount=100",
+            "--via=SOL_USDC_ORCA",
+        ])
+        .unwrap();
+        match cli.command {
+            Command::Swap { via, exact_buy, .. } => {
+                assert_eq!(via, "SOL_USDC_ORCA");
+                assert!(!exact_buy);
+            }
+            _ => panic!("expected swap"),
+        }
+        assert!(Cli::try_parse_from(["apricot", "repay", "USDC", "half"]).is_err());
+        for amount in ["0", "-1", "NaN", "inf"] {
+            assert!(Cli::try_parse_from(["apricot", "deposit", "USDC", amount]).is_err());
+            assert!(Cli::try_parse_from(["apricot", "repay", "USDC", amount]).is_err());
+        }
+        assert!(Cli::try_parse_from(["apricot", "lp", "redeem", "SOL_USDC_ORCA", "1"]).is_ok());
+        assert!(Cli::try_parse_from([
+            "apricot",
+            "lp",
+            "redeem",
+            "SOL_USDC_ORCA",
+            "1",
+            "--min-left-amount=-1",
+        ])
+        .is_err());
+
+        let cli = Cli::try_parse_from(["apricot", "user", "--price", "SOL=150.5"]).unwrap();
+        match cli.command {
+            Command::User { prices, .. } => assert_eq!(prices, vec![("SOL".to_string(), 150.5)]),
+            _ => panic!("expected user"),
+        }
+        assert!(Cli::try_parse_from(["apricot", "user", "--price", "SOL"]).is_err());
+    }
+
+    #[test]
+    fn test_context() {
+        let cli = Cli::try_parse_from(["apricot", "--cluster", "devnet", "refresh-user"]).unwrap();
+        assert!(Context::new(&cli).is_err());
+
+        let cli = Cli::try_parse_from(["apricot", "-u", "http://127.0.0.1:8899", "refresh-user"])
+            .unwrap();
+        let ctx = Context::new(&cli).unwrap();
+        assert_eq!(ctx.env().cluster.rpc_url(), "http://127.0.0.1:8899");
+        assert_eq!(
+            ctx.pool("USDC").unwrap().pool_id,
+            ctx.pool("3").unwrap().pool_id
+        );
+        assert!(ctx.pool("NOPE").is_err());
+        assert!(ctx.lp_pool("USDC").is_err());
+        let prices = ctx.prices(&[("USDC".to_string(), 1.0)]).unwrap();
+        assert_eq!(prices.get(&ctx.pool("USDC").unwrap().pool_id), Some(&1.0));
+        assert!(ctx.prices(&[("NOPE".to_string(), 1.0)]).is_err());
+        let usdc = ctx.pool("USDC").unwrap().pool_id;
+        assert_eq!(ctx.native_amount(usdc, 1.5).unwrap(), 1_500_000);
+        assert_eq!(ctx.native_amount(usdc, 0.29).unwrap(), 290_000);
+        assert!(ctx.native_amount(255, 1.0).is_err());
+
+        // dry runs need no keypair
+        let wallet = Pubkey::new_unique().to_string();
+        let cli = Cli::try_parse_from([
+            "apricot",
+            "--keypair",
+            "/nonexistent",
+            "--dry-run",
+            "--wallet",
+            &wallet,
+            "refresh-user",
+        ])
+        .unwrap();
+        let ctx = Context::new(&cli).unwrap();
+        assert_eq!(ctx.wallet().unwrap().to_string(), wallet);
+        assert!(ctx.keypair().is_err());
+        assert!(Cli::try_parse_from(["apricot", "--wallet", &wallet, "refresh-user"]).is_err());
+    }
+}
@@ -0,0 +1,155 @@
+use std::fmt;
+
+use crate::state::{AssetPool, RawAmt, UserAssetInfo, UserInfo};
+
+// Off-chain projection of interest accrual, doing what CMD_REFRESH_USER does on chain: pools grow
+// their indices and users catch up with them. Only the rates are the program's, APR as commented
+// on AssetPool and recomputed with AssetPool::calculate_interest_rate. Continuous compounding and
+// the reserve_factor share of borrow interest going to fee_amount are this module's model, no SDK
+// implements accrual, so projections are estimates the next refresh_user settles.
+
+pub const SECONDS_PER_YEAR: f64 = 365.0 * 24.0 * 3600.0;
+
+// error of project_user and reward::project_rewards
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ProjectionError {
+    // the user has an asset in a pool that wasn't given
+    MissingPool { pool_id: u8 },
+}
+
+impl fmt::Display for ProjectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ProjectionError::MissingPool { pool_id } => write!(f, "pool {} not given", pool_id),
+        }
+    }
+}
+
+impl std::error::Error for ProjectionError {}
+
+fn growth(apr: f64, seconds: u64) -> f64 {
+    (apr * seconds as f64 / SECONDS_PER_YEAR).exp()
+}
+
+// pool as of timestamp at its current rates, timestamps before last_update_time change nothing
+pub fn project_pool(pool: &AssetPool, timestamp: u64) -> AssetPool {
+    let mut pool = *pool;
+    let seconds = timestamp.saturating_sub(pool.last_update_time);
+    if seconds == 0 {
+        return pool;
+    }
+    let deposit_growth = growth(pool.current_deposit_rate, seconds);
+    let borrow_growth = growth(pool.current_borrow_rate, seconds);
+
+    let borrow_before = { pool.borrow_amount }.to_native_amount();
+    pool.deposit_amount = { pool.deposit_amount }.scaled(deposit_growth);
+    pool.borrow_amount = { pool.borrow_amount }.scaled(borrow_growth);
+    pool.deposit_index *= deposit_growth;
+    pool.borrow_index *= borrow_growth;
+
+    let borrow_interests = { pool.borrow_amount }.to_native_amount() - borrow_before;
+    let fees = (borrow_interests as f64 * pool.reserve_factor) as u64;
+    pool.fee_amount = RawAmt::from_native_amount({ pool.fee_amount }.to_native_amount() + fees);
+    pool.last_update_time = timestamp;
+
+    let (deposit_rate, borrow_rate) = pool.calculate_new_interest_rate(0, 0);
+    pool.current_deposit_rate = deposit_rate;
+    pool.current_borrow_rate = borrow_rate;
+    pool
+}
+
+// catches asset up with the indices of pool, which must already be projected
+pub fn accrue_user_asset(asset: &mut UserAssetInfo, pool: &AssetPool) {
+    if asset.deposit_index > 0.0 {
+        let before = { asset.deposit_amount }.to_native_amount();
+        asset.deposit_amount =
+            { asset.deposit_amount }.scaled(pool.deposit_index / asset.deposit_index);
+        asset.deposit_interests += { asset.deposit_amount }.to_native_amount() - before;
+    }
+    asset.deposit_index = pool.deposit_index;
+
+    if asset.borrow_index > 0.0 {
+        let before = { asset.borrow_amount }.to_native_amount();
+        asset.borrow_amount =
+            { asset.borrow_amount }.scaled(pool.borrow_index / asset.borrow_index);
+        asset.borrow_interests += { asset.borrow_amount }.to_native_amount() - before;
+    }
+    asset.borrow_index = pool.borrow_index;
+}
+
+// user and the pools they use as of timestamp. pools are taken as read from chain.
+pub fn project_user(
+    user: &UserInfo,
+    pools: &[AssetPool],
+    timestamp: u64,
+) -> Result<(UserInfo, Vec<AssetPool>), ProjectionError> {
+    let mut user = *user;
+    let mut projected = Vec::with_capacity(user.num_assets as usize);
+    let num_assets = (user.num_assets as usize).min(user.user_asset_info.len());
+    for i in 0..num_assets {
+        let pool_id = user.user_asset_info[i].pool_id;
+        let pool = pools
+            .iter()
+            .find(|pool| pool.pool_id == pool_id)
+            .ok_or(ProjectionError::MissingPool { pool_id })?;
+        let pool = project_pool(pool, timestamp);
+        let mut asset = user.user_asset_info[i];
+        accrue_user_asset(&mut asset, &pool);
+        user.user_asset_info[i] = asset;
+        projected.push(pool);
+    }
+    user.last_update_timestamp = user.last_update_timestamp.max(timestamp);
+    Ok((user, projected))
+}
+
+#[cfg(test)]
+pub mod accrual_test {
+    use super::*;
+    use crate::utils;
+
+    #[test]
+    fn test_project_user() {
+        let mut pool_data = vec![0u8; std::mem::size_of::<AssetPool>()];
+        let pool = utils::try_cast_mut::<AssetPool>(&mut pool_data).unwrap();
+        pool.pool_id = 3;
+        pool.deposit_amount = RawAmt::from_native_amount(1_000_000_000);
+        pool.borrow_amount = RawAmt::from_native_amount(500_000_000);
+        pool.deposit_index = 1.0;
+        pool.borrow_index = 1.0;
+        pool.current_deposit_rate = 0.05;
+        pool.current_borrow_rate = 0.1;
+        pool.base_rate = 0.1;
+        pool.last_update_time = 1_000;
+
+        let mut user_data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user = utils::try_cast_mut::<UserInfo>(&mut user_data).unwrap();
+        user.num_assets = 1;
+        user.user_asset_info[0].pool_id = 3;
+        user.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(100_000_000);
+        user.user_asset_info[0].deposit_index = 1.0;
+
+        let year = 1_000 + SECONDS_PER_YEAR as u64;
+        let (user, pools) = project_user(
+            UserInfo::from_bytes(&user_data),
+            &[*AssetPool::from_bytes(&pool_data)],
+            year,
+        )
+        .unwrap();
+        let asset = user.user_asset_info[0];
+        assert!(({ asset.deposit_index } - 0.05f64.exp()).abs() < 1e-12);
+        assert_eq!({ asset.deposit_interests }, 5_127_109);
+        assert_eq!({ asset.deposit_amount }.to_native_amount(), 105_127_109);
+        assert_eq!({ pools[0].borrow_amount }.to_native_amount(), 552_585_459);
+        assert_eq!({ pools[0].last_update_time }, year);
+        assert_eq!({ user.last_update_timestamp }, year);
+
+        // projecting to the same time again changes nothing
+        let (again, _) = project_user(&user, &pools, year).unwrap();
+        assert_eq!({ again.user_asset_info[0].deposit_interests }, 5_127_109);
+
+        assert_eq!(
+            project_user(&user, &[], year).err(),
+            Some(ProjectionError::MissingPool { pool_id: 3 })
+        );
+    }
+}
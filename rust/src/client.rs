@@ -0,0 +1,690 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    system_program,
+};
+use solana_rpc_client::nonblocking::rpc_client::RpcClient;
+use solana_rpc_client_api::client_error::Error as RpcClientError;
+use solana_sdk::{
+    account::Account,
+    commitment_config::CommitmentConfig,
+    signature::{Signature, Signer},
+    transaction::Transaction,
+};
+use std::fmt;
+
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::error::ApricotDecodeError;
+use crate::snapshot::PortfolioSnapshot;
+use crate::state::{AssetPool, UserInfo};
+use crate::utils::{PageStrategy, Pod, UserPagesStats};
+
+// Async access to an Apricot deployment: fetching and decoding its accounts, and building, signing
+// and sending the common user actions. Instructions are built with the env's builders, so the
+// client works against any deployment ApricotEnv describes. mock::MockRpc stands in for the RPC
+// node in tests.
+
+#[derive(Debug)]
+pub enum ApricotClientError {
+    Rpc(Box<RpcClientError>),
+    Decode {
+        key: Pubkey,
+        err: ApricotDecodeError,
+    },
+    AccountNotFound(Pubkey),
+    // wallet has no active UserInfo, deposit first
+    UserNotFound(Pubkey),
+    UnknownPool(u8),
+    // every users page is full, no new user can register
+    NoFreePage,
+    // the user kept entering pools while portfolio_snapshot was reading them
+    UnstableSnapshot(Pubkey),
+    // an account list doesn't match the keys it was requested for
+    AccountCount {
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl fmt::Display for ApricotClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApricotClientError::Rpc(err) => write!(f, "RPC request failed: {}", err),
+            ApricotClientError::Decode { key, err } => {
+                write!(f, "cannot decode account {}: {}", key, err)
+            }
+            ApricotClientError::AccountNotFound(key) => write!(f, "account {} not found", key),
+            ApricotClientError::UserNotFound(wallet) => {
+                write!(f, "wallet {} has no active Apricot account", wallet)
+            }
+            ApricotClientError::UnknownPool(pool_id) => write!(f, "unknown pool {}", pool_id),
+            ApricotClientError::NoFreePage => write!(f, "all users pages are full"),
+            ApricotClientError::UnstableSnapshot(wallet) => {
+                write!(
+                    f,
+                    "pools of {} changed during every snapshot attempt",
+                    wallet
+                )
+            }
+            ApricotClientError::AccountCount { expected, actual } => {
+                write!(f, "expected {} accounts, got {}", expected, actual)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApricotClientError {}
+
+impl From<RpcClientError> for ApricotClientError {
+    fn from(err: RpcClientError) -> Self {
+        ApricotClientError::Rpc(Box::new(err))
+    }
+}
+
+pub type ClientResult<T> = Result<T, ApricotClientError>;
+
+pub struct ApricotClient {
+    rpc: RpcClient,
+    env: ApricotEnv,
+    page_strategy: PageStrategy,
+}
+
+impl ApricotClient {
+    // connects to env's cluster with confirmed commitment
+    pub fn new(env: ApricotEnv) -> Self {
+        let rpc = RpcClient::new_with_commitment(
+            env.cluster.rpc_url().to_string(),
+            CommitmentConfig::confirmed(),
+        );
+        ApricotClient::with_rpc(rpc, env)
+    }
+
+    pub fn with_rpc(rpc: RpcClient, env: ApricotEnv) -> Self {
+        ApricotClient {
+            rpc,
+            env,
+            page_strategy: PageStrategy::MostFree,
+        }
+    }
+
+    // how deposit picks the users page of a new user
+    pub fn with_page_strategy(mut self, page_strategy: PageStrategy) -> Self {
+        self.page_strategy = page_strategy;
+        self
+    }
+
+    pub fn rpc(&self) -> &RpcClient {
+        &self.rpc
+    }
+
+    pub fn env(&self) -> &ApricotEnv {
+        &self.env
+    }
+
+    pub async fn account(&self, key: &Pubkey) -> ClientResult<Option<Account>> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(key, self.rpc.commitment())
+            .await?
+            .value)
+    }
+
+    // None when the wallet never deposited
+    pub async fn user_info(&self, wallet: &Pubkey) -> ClientResult<Option<UserInfo>> {
+        let key = self.env.user_info_k(wallet);
+        match self.account(&key).await? {
+            Some(account) => {
+                let user_info = self.decode(&key, &account, UserInfo::try_from_bytes)?;
+                Ok(Some(user_info))
+            }
+            None => Ok(None),
+        }
+    }
+
+    // UserInfo of a wallet that can deposit without registering, see UserInfo::is_active
+    pub async fn active_user_info(&self, wallet: &Pubkey) -> ClientResult<Option<UserInfo>> {
+        let user_info = self.user_info(wallet).await?;
+        Ok(user_info.filter(UserInfo::is_active))
+    }
+
+    pub async fn asset_pool(&self, pool_id: u8) -> ClientResult<AssetPool> {
+        let key = self.env.asset_pool_k(pool_id);
+        let account = self.required_account(&key).await?;
+        self.decode(&key, &account, AssetPool::try_from_bytes)
+    }
+
+    pub async fn user_pages_stats(&self) -> ClientResult<Box<UserPagesStats>> {
+        let key = self.env.user_pages_stats_k();
+        let account = self.required_account(&key).await?;
+        self.decode(&key, &account, UserPagesStats::try_from_bytes)
+            .map(Box::new)
+    }
+
+    // deposit or add_user_and_deposit, see ApricotEnv::smart_deposit. New users go on a page
+    // picked by the client's PageStrategy, tokens come from wallet's associated token account.
+    pub async fn deposit_instructions(
+        &self,
+        wallet: &Pubkey,
+        pool_id: u8,
+        amount: u64,
+    ) -> ClientResult<Vec<Instruction>> {
+        let mint = self.mint(pool_id)?;
+        let user_spl = consts::get_associated_token_k(wallet, &mint);
+        // both in one request
+        let user_info_k = self.env.user_info_k(wallet);
+        let stats_k = self.env.user_pages_stats_k();
+        let accounts = self
+            .rpc
+            .get_multiple_accounts_with_commitment(&[user_info_k, stats_k], self.rpc.commitment())
+            .await?
+            .value;
+        let user_info = match &accounts[0] {
+            Some(account) => Some(self.decode(&user_info_k, account, UserInfo::try_from_bytes)?),
+            None => None,
+        };
+        let stats_account = accounts[1]
+            .as_ref()
+            .ok_or(ApricotClientError::AccountNotFound(stats_k))?;
+        let stats = self.decode(&stats_k, stats_account, UserPagesStats::try_from_bytes)?;
+
+        let ix = self
+            .env
+            .smart_deposit(
+                wallet,
+                &user_spl,
+                amount,
+                pool_id,
+                user_info.as_ref(),
+                &stats,
+                self.page_strategy,
+            )
+            .ok_or(ApricotClientError::NoFreePage)?;
+        Ok(vec![ix])
+    }
+
+    // amount is ignored when withdraw_all is set. Creates wallet's associated token account first
+    // when it doesn't exist yet.
+    pub async fn withdraw_instructions(
+        &self,
+        wallet: &Pubkey,
+        pool_id: u8,
+        withdraw_all: bool,
+        amount: u64,
+    ) -> ClientResult<Vec<Instruction>> {
+        let mint = self.mint(pool_id)?;
+        let user_info = self
+            .active_user_info(wallet)
+            .await?
+            .ok_or(ApricotClientError::UserNotFound(*wallet))?;
+        let user_spl = consts::get_associated_token_k(wallet, &mint);
+
+        let mut ixs = vec![];
+        if self.account(&user_spl).await?.is_none() {
+            ixs.push(create_associated_token_account(wallet, wallet, &mint));
+        }
+        let amount = if withdraw_all { 0 } else { amount };
+        ixs.push(self.env.withdraw(
+            wallet,
+            &user_spl,
+            withdraw_all,
+            amount,
+            pool_id,
+            user_info.page_id,
+        ));
+        Ok(ixs)
+    }
+
+    pub async fn deposit(
+        &self,
+        signer: &dyn Signer,
+        pool_id: u8,
+        amount: u64,
+    ) -> ClientResult<Signature> {
+        let ixs = self
+            .deposit_instructions(&signer.pubkey(), pool_id, amount)
+            .await?;
+        self.send(&ixs, signer).await
+    }
+
+    pub async fn withdraw(
+        &self,
+        signer: &dyn Signer,
+        pool_id: u8,
+        withdraw_all: bool,
+        amount: u64,
+    ) -> ClientResult<Signature> {
+        let ixs = self
+            .withdraw_instructions(&signer.pubkey(), pool_id, withdraw_all, amount)
+            .await?;
+        self.send(&ixs, signer).await
+    }
+
+    // signs ixs with signer as fee payer and waits for confirmation
+    pub async fn send(&self, ixs: &[Instruction], signer: &dyn Signer) -> ClientResult<Signature> {
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let tx =
+            Transaction::new_signed_with_payer(ixs, Some(&signer.pubkey()), &[signer], blockhash);
+        Ok(self.rpc.send_and_confirm_transaction(&tx).await?)
+    }
+
+    fn mint(&self, pool_id: u8) -> ClientResult<Pubkey> {
+        self.env
+            .config
+            .pools
+            .by_pool_id(pool_id)
+            .map(|pool| pool.mint)
+            .ok_or(ApricotClientError::UnknownPool(pool_id))
+    }
+
+    async fn required_account(&self, key: &Pubkey) -> ClientResult<Account> {
+        self.account(key)
+            .await?
+            .ok_or(ApricotClientError::AccountNotFound(*key))
+    }
+
+    // copies the account out after checking it belongs to the env's program
+    fn decode<T: Pod>(
+        &self,
+        key: &Pubkey,
+        account: &Account,
+        try_from_bytes: impl Fn(&[u8]) -> Result<&T, ApricotDecodeError>,
+    ) -> ClientResult<T> {
+        check_program_owner(&self.env, key, account)?;
+        try_from_bytes(&account.data)
+            .copied()
+            .map_err(|err| ApricotClientError::Decode { key: *key, err })
+    }
+
+    // UserInfo and the user's AssetPools as of one slot. The first request tells
+    // which pools the user is in and the second reads them together with UserInfo again, further
+    // requests are only made when the user enters a pool in between.
+    pub async fn portfolio_snapshot(&self, wallet: &Pubkey) -> ClientResult<PortfolioSnapshot> {
+        let mut pool_ids = vec![];
+        for _ in 0..MAX_SNAPSHOT_REQUESTS {
+            let keys = PortfolioSnapshot::keys(&self.env, wallet, &pool_ids);
+            let response = self
+                .rpc
+                .get_multiple_accounts_with_commitment(&keys, self.rpc.commitment())
+                .await?;
+            let snapshot = PortfolioSnapshot::decode(
+                &self.env,
+                wallet,
+                response.context.slot,
+                &pool_ids,
+                &response.value,
+            )?;
+            if snapshot.is_complete() {
+                return Ok(snapshot);
+            }
+            pool_ids = snapshot.user_pool_ids();
+        }
+        Err(ApricotClientError::UnstableSnapshot(*wallet))
+    }
+}
+
+const MAX_SNAPSHOT_REQUESTS: usize = 4;
+
+pub(crate) fn check_program_owner(
+    env: &ApricotEnv,
+    key: &Pubkey,
+    account: &Account,
+) -> ClientResult<()> {
+    if account.owner != env.program_id() {
+        return Err(ApricotClientError::Decode {
+            key: *key,
+            err: ApricotDecodeError::WrongOwner {
+                expected: env.program_id(),
+                actual: account.owner,
+            },
+        });
+    }
+    Ok(())
+}
+
+// CreateIdempotent of the associated token program, a no-op when the account already exists
+pub fn create_associated_token_account(
+    payer: &Pubkey,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: consts::associated_token_program::ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(consts::get_associated_token_k(owner, mint), false),
+            AccountMeta::new_readonly(*owner, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(system_program::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ],
+        data: vec![1],
+    }
+}
+
+// In-process stand-in for an RPC node. It serves accounts set with set_account and accepts every
+// transaction, recording it instead of executing it. Built for this crate's tests and with the
+// test-utils feature.
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock {
+    use async_trait::async_trait;
+    use base64::Engine;
+    use serde_json::{json, Value};
+    use solana_program::pubkey::Pubkey;
+    use solana_rpc_client::{
+        nonblocking::rpc_client::RpcClient,
+        rpc_client::RpcClientConfig,
+        rpc_sender::{RpcSender, RpcTransportStats},
+    };
+    use solana_rpc_client_api::{client_error, request::RpcError, request::RpcRequest};
+    use solana_sdk::{
+        account::Account, commitment_config::CommitmentConfig, hash::Hash, transaction::Transaction,
+    };
+    use std::collections::HashMap;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    pub struct MockRpc {
+        state: Arc<Mutex<MockState>>,
+    }
+
+    #[derive(Default)]
+    struct MockState {
+        slot: u64,
+        accounts: HashMap<Pubkey, Account>,
+        sent: Vec<Transaction>,
+        requests: Vec<RpcRequest>,
+    }
+
+    impl MockRpc {
+        pub fn new() -> Self {
+            MockRpc::default()
+        }
+
+        // RpcClient talking to this mock, clones share the same accounts
+        pub fn rpc_client(&self) -> RpcClient {
+            RpcClient::new_sender(
+                self.clone(),
+                RpcClientConfig::with_commitment(CommitmentConfig::confirmed()),
+            )
+        }
+
+        pub fn set_account(&self, key: Pubkey, account: Account) {
+            self.state.lock().unwrap().accounts.insert(key, account);
+        }
+
+        // account owned by owner holding data
+        pub fn set_data(&self, key: Pubkey, owner: Pubkey, data: Vec<u8>) {
+            self.set_account(
+                key,
+                Account {
+                    lamports: 1_000_000,
+                    data,
+                    owner,
+                    executable: false,
+                    rent_epoch: 0,
+                },
+            );
+        }
+
+        pub fn remove_account(&self, key: &Pubkey) {
+            self.state.lock().unwrap().accounts.remove(key);
+        }
+
+        pub fn set_slot(&self, slot: u64) {
+            self.state.lock().unwrap().slot = slot;
+        }
+
+        pub fn sent_transactions(&self) -> Vec<Transaction> {
+            self.state.lock().unwrap().sent.clone()
+        }
+
+        // every request served so far apart from getVersion, which RpcClient sends on its own
+        pub fn requests(&self) -> Vec<RpcRequest> {
+            self.state.lock().unwrap().requests.clone()
+        }
+
+        fn respond(&self, request: RpcRequest, params: &Value) -> Result<Value, String> {
+            let mut state = self.state.lock().unwrap();
+            if request != RpcRequest::GetVersion {
+                state.requests.push(request);
+            }
+            let context = json!({ "slot": state.slot });
+            let value = match request {
+                RpcRequest::GetVersion => {
+                    return Ok(json!({ "solana-core": "1.18.26", "feature-set": 0 }))
+                }
+                RpcRequest::GetSlot => return Ok(json!(state.slot)),
+                RpcRequest::GetAccountInfo => {
+                    let key = param_key(&params[0])?;
+                    account_json(state.accounts.get(&key))
+                }
+                RpcRequest::GetMultipleAccounts => {
+                    let keys = params[0].as_array().ok_or("expected an array of keys")?;
+                    let accounts = keys
+                        .iter()
+                        .map(|key| Ok(account_json(state.accounts.get(&param_key(key)?))))
+                        .collect::<Result<Vec<_>, String>>()?;
+                    json!(accounts)
+                }
+                RpcRequest::GetLatestBlockhash => json!({
+                    "blockhash": Hash::default().to_string(),
+                    "lastValidBlockHeight": state.slot + 150,
+                }),
+                RpcRequest::IsBlockhashValid => json!(true),
+                RpcRequest::SendTransaction => {
+                    let encoded = params[0]
+                        .as_str()
+                        .ok_or("expected an encoded transaction")?;
+                    let bytes = base64::engine::general_purpose::STANDARD
+                        .decode(encoded)
+                        .map_err(|err| err.to_string())?;
+                    let tx: Transaction =
+                        bincode::deserialize(&bytes).map_err(|err| err.to_string())?;
+                    let signature = tx.signatures[0].to_string();
+                    state.sent.push(tx);
+                    return Ok(json!(signature));
+                }
+                RpcRequest::GetSignatureStatuses => {
+                    let signatures = params[0].as_array().ok_or("expected signatures")?;
+                    let statuses = signatures
+                        .iter()
+                        .map(|_| {
+                            json!({
+                                "slot": state.slot,
+                                "confirmations": null,
+                                "status": { "Ok": null },
+                                "err": null,
+                                "confirmationStatus": "finalized",
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    json!(statuses)
+                }
+                _ => return Err(format!("{} is not supported by MockRpc", request)),
+            };
+            Ok(json!({ "context": context, "value": value }))
+        }
+    }
+
+    fn param_key(param: &Value) -> Result<Pubkey, String> {
+        let key = param.as_str().ok_or("expected a pubkey")?;
+        Pubkey::from_str(key).map_err(|err| err.to_string())
+    }
+
+    fn account_json(account: Option<&Account>) -> Value {
+        match account {
+            Some(account) => json!({
+                "lamports": account.lamports,
+                "data": [base64::engine::general_purpose::STANDARD.encode(&account.data), "base64"],
+                "owner": account.owner.to_string(),
+                "executable": account.executable,
+                "rentEpoch": account.rent_epoch,
+                "space": account.data.len(),
+            }),
+            None => Value::Null,
+        }
+    }
+
+    #[async_trait]
+    impl RpcSender for MockRpc {
+        async fn send(&self, request: RpcRequest, params: Value) -> client_error::Result<Value> {
+            self.respond(request, &params)
+                .map_err(|err| RpcError::RpcRequestError(err).into())
+        }
+
+        fn get_transport_stats(&self) -> RpcTransportStats {
+            RpcTransportStats::default()
+        }
+
+        fn url(&self) -> String {
+            "mock".to_string()
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod client_test {
+    use super::mock::MockRpc;
+    use super::*;
+    use crate::config;
+    use crate::utils;
+    use solana_sdk::signature::Keypair;
+
+    fn setup() -> (MockRpc, ApricotClient) {
+        let mock = MockRpc::new();
+        let client = ApricotClient::with_rpc(mock.rpc_client(), ApricotEnv::mainnet());
+        let mut stats = vec![0u8; std::mem::size_of::<UserPagesStats>()];
+        stats[2 * 12] = 3;
+        mock.set_data(consts::get_user_pages_stats_k(), consts::program::ID, stats);
+        (mock, client)
+    }
+
+    fn user_info_data(page_id: u16) -> Vec<u8> {
+        let mut data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        utils::try_cast_mut::<UserInfo>(&mut data).unwrap().page_id = page_id;
+        data
+    }
+
+    #[tokio::test]
+    async fn test_deposit_registers_new_user() {
+        let (mock, client) = setup();
+        let user = Keypair::new();
+        let wallet = user.pubkey();
+        let pool_id = config::usdc::POOL_ID;
+
+        assert!(client.user_info(&wallet).await.unwrap().is_none());
+        client.deposit(&user, pool_id, 100).await.unwrap();
+        let sent = mock.sent_transactions();
+        assert_eq!(sent.len(), 1);
+        let expected = instructions_of(&[client.env().add_user_and_deposit(
+            &wallet,
+            &consts::get_associated_token_k(&wallet, &config::usdc::ID),
+            100,
+            pool_id,
+            12,
+        )]);
+        assert_eq!(instructions_of_tx(&sent[0]), expected);
+
+        // registered users deposit directly
+        mock.set_data(
+            consts::get_user_info_k(&wallet),
+            consts::program::ID,
+            user_info_data(12),
+        );
+        let ixs = client
+            .deposit_instructions(&wallet, pool_id, 100)
+            .await
+            .unwrap();
+        assert_eq!(ixs[0].data[0], consts::CMD_DEPOSIT);
+
+        // a deactivated user has to register again
+        mock.set_data(
+            consts::get_user_info_k(&wallet),
+            consts::program::ID,
+            user_info_data(utils::INVALID_PAGE_ID),
+        );
+        let ixs = client
+            .deposit_instructions(&wallet, pool_id, 100)
+            .await
+            .unwrap();
+        assert_eq!(ixs[0].data[0], consts::CMD_ADD_USER_AND_DEPOSIT);
+    }
+
+    #[tokio::test]
+    async fn test_withdraw_creates_token_account() {
+        let (mock, client) = setup();
+        let wallet = Pubkey::new_unique();
+        let pool_id = config::usdc::POOL_ID;
+        match client
+            .withdraw_instructions(&wallet, pool_id, true, 0)
+            .await
+        {
+            Err(ApricotClientError::UserNotFound(key)) => assert_eq!(key, wallet),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        mock.set_data(
+            consts::get_user_info_k(&wallet),
+            consts::program::ID,
+            user_info_data(7),
+        );
+        let ixs = client
+            .withdraw_instructions(&wallet, pool_id, false, 5)
+            .await
+            .unwrap();
+        assert_eq!(ixs.len(), 2);
+        assert_eq!(ixs[0].program_id, consts::associated_token_program::ID);
+        assert_eq!(ixs[1].data[0], consts::CMD_WITHDRAW);
+
+        let user_spl = consts::get_associated_token_k(&wallet, &config::usdc::ID);
+        mock.set_data(user_spl, spl_token::ID, vec![0; 165]);
+        let ixs = client
+            .withdraw_instructions(&wallet, pool_id, false, 5)
+            .await
+            .unwrap();
+        assert_eq!(ixs.len(), 1);
+
+        // accounts of another program are rejected
+        mock.set_data(
+            consts::get_asset_pool_k(pool_id),
+            Pubkey::new_unique(),
+            vec![0; std::mem::size_of::<AssetPool>()],
+        );
+        match client.asset_pool(pool_id).await {
+            Err(ApricotClientError::Decode {
+                err: ApricotDecodeError::WrongOwner { .. },
+                ..
+            }) => {}
+            other => panic!("unexpected {:?}", other.map(|_| ())),
+        }
+    }
+
+    fn instructions_of(ixs: &[Instruction]) -> Vec<(Pubkey, Vec<Pubkey>, Vec<u8>)> {
+        ixs.iter()
+            .map(|ix| {
+                (
+                    ix.program_id,
+                    ix.accounts.iter().map(|meta| meta.pubkey).collect(),
+                    ix.data.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn instructions_of_tx(tx: &Transaction) -> Vec<(Pubkey, Vec<Pubkey>, Vec<u8>)> {
+        let keys = &tx.message.account_keys;
+        tx.message
+            .instructions
+            .iter()
+            .map(|ix| {
+                (
+                    keys[ix.program_id_index as usize],
+                    ix.accounts.iter().map(|i| keys[*i as usize]).collect(),
+                    ix.data.clone(),
+                )
+            })
+            .collect()
+    }
+}
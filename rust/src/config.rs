@@ -1,4 +1,9 @@
-use solana_program::pubkey::Pubkey;
+use crate::consts;
+use crate::instructions::SwapTarget;
+use crate::lp::{LpInfo, LpSwapInfo};
+use crate::swap::SwapAccounts;
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey};
+use std::fmt;
 
 pub mod apt {
     use solana_program::declare_id;
@@ -89,40 +94,100 @@ pub mod ust {
 }
 
 pub mod usdt_usdc_saber {
-    use solana_program::declare_id;
+    use crate::lp::SaberLpSwapInfo;
+    use solana_program::{declare_id, pubkey};
     declare_id!("2poo1w1DL6yd2WNTCnNTzDqkC6MBXq7axo77P16yrBuf");
     pub const POOL_ID: u8 = 5;
     pub const NAME: &str = "USDT_USDC_SABER";
+    pub const LEFT_POOL_ID: u8 = super::usdt::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: SaberLpSwapInfo = SaberLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("YAkoNb6HKmSxQN9L8hiBE5tPJRsniSSMzND1boHmZxe"),
+        swap_authority: pubkey!("5C1k9yV7y4CjMnKv8eGYDgWND8P89Pdfj79Trk2qmfGo"),
+        token_a_vault: pubkey!("CfWX7o2TswwbxusJ4hCaPobu2jLCb1hfXuXJQjVq3jQF"),
+        token_b_vault: pubkey!("EnTrdMMpdhugeH6Ban6gYZWXughWxKtVGfCwFn78ZmY3"),
+        token_a_fees: pubkey!("XZuQG7CQrAA6y6tHM9CLrDjDUWwuUU2SBoV7pLaGDQT"),
+        token_b_fees: pubkey!("63aJYYuZddSnCGyE8FNrCVQWnXhjh6CQSRwcDeSMhdVC"),
+        quarry: pubkey!("Hs1X5YtXwZACueUtS9azZyXFDWVxAMLvm3tttubpK7ph"),
+        rewarder: pubkey!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk"),
+        miner: miner::ID,
+    };
     pub mod miner {
         use solana_program::declare_id;
         declare_id!("GP1U66jGiiscj4HotJP7JTj76jpygdUaTUJT6HPbkoKn");
     }
 }
 pub mod msol_sol_saber {
-    use solana_program::declare_id;
+    use crate::lp::SaberLpSwapInfo;
+    use solana_program::{declare_id, pubkey};
     declare_id!("SoLEao8wTzSfqhuou8rcYsVoLjthVmiXuEjzdNPMnCz");
     pub const POOL_ID: u8 = 40;
     pub const NAME: &str = "mSOL_SOL_SABER";
+    pub const LEFT_POOL_ID: u8 = super::msol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: SaberLpSwapInfo = SaberLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("Lee1XZJfJ9Hm2K1qTyeCz1LXNc1YBZaKZszvNY4KCDw"),
+        swap_authority: pubkey!("2Sj4MZvmLhud4uRmGHJvDxq612nmF4JJsU1R4ZjNNGMS"),
+        token_a_vault: pubkey!("9DgFSWkPDGijNKcLGbr3p5xoJbHsPgXUTr6QvGBJ5vGN"),
+        token_b_vault: pubkey!("2hNHZg7XBhuhHVZ3JDEi4buq2fPQwuWBdQ9xkH7t1GQX"),
+        token_a_fees: pubkey!("HzZRDMiJSqS5oxzfu17c35DChnkx58LZtas16Pgmuunn"),
+        token_b_fees: pubkey!("3oebZVvPqba2egfdcbNXa1uS13SfSebxMaNVE82FMk7R"),
+        quarry: pubkey!("7193EeecxsPPv9TMoQATTN8i1eTqEUSNU8aDLuFCQy68"),
+        rewarder: pubkey!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk"),
+        miner: miner::ID,
+    };
     pub mod miner {
         use solana_program::declare_id;
         declare_id!("73asEXQWZZqUUG58gY8vovh9wNQxUsKT7tKq8eZzPJhT");
     }
 }
 pub mod stsol_sol_saber {
-    use solana_program::declare_id;
+    use crate::lp::SaberLpSwapInfo;
+    use solana_program::{declare_id, pubkey};
     declare_id!("stSjCmjQ96BiGhTk8gkU22j1739R8YBQVMq7KXWTqUV");
     pub const POOL_ID: u8 = 41;
     pub const NAME: &str = "stSOL_SOL_SABER";
+    pub const LEFT_POOL_ID: u8 = super::stsol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: SaberLpSwapInfo = SaberLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("Lid8SLUxQ9RmF7XMqUA8c24RitTwzja8VSKngJxRcUa"),
+        swap_authority: pubkey!("8eyi347MTDeH5F6eVv2qjPxVnU685FFZLDGcj5QWHZ6y"),
+        token_a_vault: pubkey!("4PgzyzLtds9bKZ2to9PMnKqJzKEUpjvNUaeN23phegax"),
+        token_b_vault: pubkey!("AtymwxoVN9peZo7EXTcDz9jKVc4vRmisJKKrNfe3ewBa"),
+        token_a_fees: pubkey!("2AbLYRQa7PV6gG6XgMjaey18RtPh85sXFmMmP4HsDdQK"),
+        token_b_fees: pubkey!("Cv3YNq8iY1ttMS3iDgwBxd7QxnMC2pwcXUomtR7CTD8W"),
+        quarry: pubkey!("2isVhcqVVpsAeZ94d1DGVErCQ7uZQNSJ85xikN8awHeC"),
+        rewarder: pubkey!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk"),
+        miner: miner::ID,
+    };
     pub mod miner {
         use solana_program::declare_id;
         declare_id!("AE3BisWAMqs695qU7Y2L6s52v7N79MMTNQRbvqytXNJU");
     }
 }
 pub mod ust_usdc_saber {
-    use solana_program::declare_id;
+    use crate::lp::SaberLpSwapInfo;
+    use solana_program::{declare_id, pubkey};
     declare_id!("USTCmQpbUGj5iTsXdnTYHZupY1QpftDZhLokSVk6UWi");
     pub const POOL_ID: u8 = 26;
     pub const NAME: &str = "UST_USDC_SABER";
+    pub const LEFT_POOL_ID: u8 = super::ust::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: SaberLpSwapInfo = SaberLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("KwnjUuZhTMTSGAaavkLEmSyfobY16JNH4poL9oeeEvE"),
+        swap_authority: pubkey!("9osV5a7FXEjuMujxZJGBRXVAyQ5fJfBFNkyAf6fSz9kw"),
+        token_a_vault: pubkey!("J63v6qEZmQpDqCD8bd4PXu2Pq5ZbyXrFcSa3Xt1HdAPQ"),
+        token_b_vault: pubkey!("BnKQtTdLw9qPCDgZkWX3sURkBAoKCUYL1yahh6Mw7mRK"),
+        token_a_fees: pubkey!("BYgyVxdrGa3XNj1cx1XHAVyRG8qYhBnv1DS59Bsvmg5h"),
+        token_b_fees: pubkey!("G9nt2GazsDj3Ey3KdA49Sfaq9K95Dc72Ejps4NKTP2SR"),
+        quarry: pubkey!("BYEUtsLjYAVHRiRR3Avjqnd2RQLRL8n933N52p9kSX2y"),
+        rewarder: pubkey!("rXhAofQCT7NN9TUqigyEAUzV1uLL4boeD8CRkNBSkYk"),
+        miner: miner::ID,
+    };
     pub mod miner {
         use solana_program::declare_id;
         declare_id!("HgkCE5MPdDRKaYAY51smAJTToYsjUn2oLy5K9ZQYL4WP");
@@ -130,10 +195,31 @@ pub mod ust_usdc_saber {
 }
 
 pub mod usdc_usdt_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("H2uzgruPvonVpCRhwwdukcpXK8TG17swFNzYFr2rtPxy");
     pub const POOL_ID: u8 = 8;
     pub const NAME: &str = "USDC_USDT_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdt::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("F13xvvx45jVGd84ynK3c8T89UejQVxjCLtmHfPmAXAHP"),
+        swap_authority: pubkey!("3cGHDS8uWhdxQj14vTmFtYHX3NMouPpE4o9MjQ43Bbf4"),
+        swap_token_a: pubkey!("6uUn2okWk5v4x9Gc4n2LLGHtWoa9tmizHq1363dW7t9W"),
+        swap_token_b: pubkey!("AiwmnLy7xPT28dqZpkRm6i1ZGwELUCzCsuN92v4JkSeU"),
+        fee_account: pubkey!("B4RNxMJGRzKFQyTq2Uwkmpyjtew13n7KtdqZy6qgENTu"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("9hPRfmQmZYiL4ZtuvGBk5SjMzmFCQ2h9a4GKoM82BR84"),
+            farm_token_mint: pubkey!("GjpXgKwn4VW4J2pZdS3dovM58hiXWLJtopTfqG83zY2f"),
+            global_farm_state: pubkey!("5psKJrxWnPmoAbCxk3An2CGh7wHAX2cWddf5vZuYbbVw"),
+            global_reward_token_vault: pubkey!("AYbtHmuJxXpo91m988UdyTtzC6J72WvMAW7XkXqFhAbz"),
+            reward_token_authority: pubkey!("5YGvg6mfuvJtHdVWDXTs4sYy6GwQAUduK8qurDcL111S"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("6s2gUuvYKF56j9TkmvLw2zQc3XiCqFa5ZJ7mWzTx2Xtp");
@@ -144,10 +230,31 @@ pub mod usdc_usdt_orca {
     }
 }
 pub mod sol_usdc_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("APDFRM3HMr8CAGXwKHiu2f5ePSpaiEJhaURwhsRrUUt9");
     pub const POOL_ID: u8 = 15;
     pub const NAME: &str = "SOL_USDC_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("EGZ7tiLeH62TPV1gL8WwbXGzEPa9zmcpVnnkPKKnrE2U"),
+        swap_authority: pubkey!("JU8kmKzDHF9sXWsnoznaFDFezLsE5uomX2JkRMbmsQP"),
+        swap_token_a: pubkey!("ANP74VNsHwSrq9uUSjiSNyNWvf6ZPrKTmE4gHoNd13Lg"),
+        swap_token_b: pubkey!("75HgnSvXbWKZBpZHveX68ZzAhDqMzNDS29X6BGLtxMo1"),
+        fee_account: pubkey!("8JnSiuvQq3BVuCU3n4DrSTw9chBSPvEMswrhtifVkr1o"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("7ipefo5V3QEJWeuT2PohFSEUaranZxMSeWQo2rcNigr3"),
+            farm_token_mint: pubkey!("FFdjrSvNALfdgxANNpt3x85WpeVMdQSH5SEP2poM8fcK"),
+            global_farm_state: pubkey!("85HrPbJtrN82aeB74WTwoFxcNgmf5aDNP2ENngbDpd5G"),
+            global_reward_token_vault: pubkey!("kjjFC8RAF7GuBQ9iYgyTcPmvsRafJ2Ec2AmoS6DjakJ"),
+            reward_token_authority: pubkey!("MDcWkwPqr5HrA91g4GGax7bVP1NDDetnR12nGhoAdYj"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("BHRqAQrYye19sQho6knsGazThRaKg4nVeZBLb1iz8RUq");
@@ -158,10 +265,39 @@ pub mod sol_usdc_orca {
     }
 }
 pub mod msol_sol_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("29cdoMgu6MS2VXpcMo1sqRdWEzdUR9tjvoh8fcK8Z87R");
     pub const POOL_ID: u8 = 16;
     pub const NAME: &str = "mSOL_SOL_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::msol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("9EQMEzJdE2LDAY1hw1RytpufdwAXzatYfQ3M2UuT9b88"),
+        swap_authority: pubkey!("6cwehd4xhKkJ2s7iGh4CaDb7KhMgqczSBnyNJieUYbHn"),
+        swap_token_a: pubkey!("6xmki5RtGNHrfhTiHFfp9k3RQ9t8qgL1cYP2YCG2h179"),
+        swap_token_b: pubkey!("Ew2coQtVGLeca31vqB2ssHntjzZgUy1ad9VuuAX8yw7p"),
+        fee_account: pubkey!("6j2tt2UVYMQwqG3hRtyydW3odzBFwy3pN33tyB3xCKQ6"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("DuTZUmTRydVc3EN78brdYFUfskn6s93zH4WhY3Fo53AJ"),
+            farm_token_mint: pubkey!("3RTGL7gPF4V1ns1AeGFApT7cBEGVDfmJ77DqQi9AC6uG"),
+            global_farm_state: pubkey!("JADWjBW1Xs8WhW8kj3GTCRQn3LR4gwvbFTEMwv9ZNxQh"),
+            global_reward_token_vault: pubkey!("7dpUACKvEiuq5kyoGtgiA131hYwdxfFhEeD5TMT4mnzG"),
+            reward_token_authority: pubkey!("CtXKDXJ4wzgto48QQFANestEgtov5dJRrs9qpRw7BV1h"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: Some(OrcaFarmInfo {
+            global_lp_vault: pubkey!("AEZpFdJ5hA7MwVS7AReBbS9pMhoYRhLXgDyc1GWbSoXc"),
+            farm_token_mint: pubkey!("576ABEdvLG1iFU3bLC8AMJ3mo5LhfgPPhMtTeVAGG6u7"),
+            global_farm_state: pubkey!("2SciNw7cEsKJc1PMRDzWCcEzvuScmEaUgmrJXCi9UFxY"),
+            global_reward_token_vault: pubkey!("DCHpFt1bCk9mTudj6VsKbADvUPT3tAJvJ2rcBZQry8Wz"),
+            reward_token_authority: pubkey!("5uk8F4MaFSu1pF9Q7k8xcyWgqyo9q2dqr3Kb4Esvd1n3"),
+            user_farm_state: user_farm_state_2nd::ID,
+            user_reward_token_account: reward_dd_account_2nd::ID,
+        }),
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("AuiCj6vtkhctyfbvFHqcr5oLifGLZz77QVLUt5iVjXWm");
@@ -184,10 +320,31 @@ pub mod msol_sol_orca {
     }
 }
 pub mod orca_usdc_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("n8Mpu28RjeYD7oUX3LG1tPxzhRZh3YYLRSHcHRdS3Zx");
     pub const POOL_ID: u8 = 17;
     pub const NAME: &str = "ORCA_USDC_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::orca::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("2p7nYbtPBgtmY69NsE8DAW6szpRJn7tQvDnqvoEWQvjY"),
+        swap_authority: pubkey!("3fr1AhdiAmWLeNrS24CMoAu9pPgbzVhwLtJ6QUPmw2ob"),
+        swap_token_a: pubkey!("9vYWHBPz817wJdQpE8u3h8UoY3sZ16ZXdCcvLB7jY4Dj"),
+        swap_token_b: pubkey!("6UczejMUv1tzdvUzKpULKHxrK9sqLm8edR1v9jinVWm9"),
+        fee_account: pubkey!("7CXZED4jfRp3qdHB9Py3up6v1C4UhHofFvfT6RXbJLRN"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("45BAAQCZYd2kP3Z3WvRwdtfUhvuW4FvpqVK4m8qrR5x1"),
+            farm_token_mint: pubkey!("Gc7W5U66iuHQcC1cQyeX9hxkPF2QUVJPTf1NWbW8fNrt"),
+            global_farm_state: pubkey!("9S1BsxbDNQXQccjFamVEGgxiYQHTeudvhEYwFr4oWeaf"),
+            global_reward_token_vault: pubkey!("DEiqe2Ta9TRMRtWdBqiFV13dhVrqCeG8MMmVwywvXvJo"),
+            reward_token_authority: pubkey!("66xaEjFoYfRcspc18oDj61mXDyznr9zam6tFNeqvs2jK"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("3DmAD81qp5ZtxFUjtcrubuRrzCsJSDXHbR1P5VPeVuyz");
@@ -198,10 +355,31 @@ pub mod orca_usdc_orca {
     }
 }
 pub mod orca_sol_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("2uVjAuRXavpM6h1scGQaxqb6HVaNRn6T2X7HHXTabz25");
     pub const POOL_ID: u8 = 18;
     pub const NAME: &str = "ORCA_SOL_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::orca::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("2ZnVuidTHpi5WWKUwFXauYGhvdT9jRKYv5MDahtbwtYr"),
+        swap_authority: pubkey!("2PH1quJj9MHQXATCmNZ6qQ2gZqM8R236DpKaz99ggVpm"),
+        swap_token_a: pubkey!("AioST8HKQJRqjE1mknk4Rydc8wVADhdQwRJmAAYX1T6Z"),
+        swap_token_b: pubkey!("73zdy95DynZP4exdpuXTDsexcrWbDJX9TFi2E6CDzXh4"),
+        fee_account: pubkey!("4Zc4kQZhRQeGztihvcGSWezJE1k44kKEgPCAkdeBfras"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("7N7zxoDMMV1sCDiVEzinTyQxS2GoN388QprMCQX38BeT"),
+            farm_token_mint: pubkey!("B5waaKnsmtqFawPspUwcuy1cRjAC7u2LrHSwxPSxK4sZ"),
+            global_farm_state: pubkey!("F6pi7SyXWx56fP96mYQ4Yfh4yZ7oGNtDjwSYHT5Mz7Ld"),
+            global_reward_token_vault: pubkey!("CSbYA7Cd65Vis2oqX797zmnWmpgENmqrPdmPbTbRPykd"),
+            reward_token_authority: pubkey!("98RAHBKRTTC87nNwug1GEAnLVgouk9nRaa3u14jrp6Zz"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("3dAg8zU3VLcE3vfpxsxbnfbKoUfg6G4kGcETxkiQ4oKr");
@@ -212,10 +390,31 @@ pub mod orca_sol_orca {
     }
 }
 pub mod eth_usdc_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("3e1W6Aqcbuk2DfHUwRiRcyzpyYRRjg6yhZZcyEARydUX");
     pub const POOL_ID: u8 = 19;
     pub const NAME: &str = "ETH_USDC_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::eth::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("FgZut2qVQEyPBibaTJbbX2PxaMZvT1vjDebiVaDp5BWP"),
+        swap_authority: pubkey!("4dfCZR32xXhoTgMRhnViNaTFwiKP9A34TDjHCR3xM5rg"),
+        swap_token_a: pubkey!("H9h5yTBfCHcb4eRP87fXczzXgNaMzKihr7bf1sjw7iuZ"),
+        swap_token_b: pubkey!("JA98RXv2VdxQD8pRQq4dzJ1Bp4nH8nokCGmxvPWKJ3hx"),
+        fee_account: pubkey!("DLWewB12jzGn4wXJmFCddWDeof1Ma4cZYNRv9CP5hTvX"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("6zoYTvgLd4UAhKSPwirEU9VNNNkpezwq8AM4jXW1Qop9"),
+            farm_token_mint: pubkey!("HDP2AYFmvLz6sWpoSuNS62JjvW4HjMKp7doXucqpWN56"),
+            global_farm_state: pubkey!("FpezTR76RRjgpBb9HhR6ap8BgQfkHyNMQSqJDcoXpjAb"),
+            global_reward_token_vault: pubkey!("9MWJmWVAGQ9C9SxwWKidStAA8HjDHpnZ7KfKgVJdrNtj"),
+            reward_token_authority: pubkey!("DFTLJrgsn7cLNX9hbqiUwM8C1y6f7AfyvEmbsFSkjQNR"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("jkLcyt7rqAaioqKgG3UYzP56XigyqojaJNLzvd8s7GR");
@@ -226,10 +425,31 @@ pub mod eth_usdc_orca {
     }
 }
 pub mod sol_usdt_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("FZthQCuYHhcfiDma7QrX7buDHwrZEd7vL8SjS6LQa3Tx");
     pub const POOL_ID: u8 = 20;
     pub const NAME: &str = "SOL_USDT_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdt::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("Dqk7mHQBx2ZWExmyrR2S8X6UG75CrbbpK2FSBZsNYsw6"),
+        swap_authority: pubkey!("2sxKY7hxVFrY5oNE2DgaPAJFamMzsmFLM2DgVcjK5yTy"),
+        swap_token_a: pubkey!("DTb8NKsfhEJGY1TrA7RXN6MBiTrjnkdMAfjPEjtmTT3M"),
+        swap_token_b: pubkey!("E8erPjPEorykpPjFV9yUYMYigEWKQUxuGfL2rJKLJ3KU"),
+        fee_account: pubkey!("BBKgw75FivTYXj85D2AWyVdaTdTWuSuHVXRm1Xu7fipb"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("EXxH5tKDHLy68nWXS8w1BRUsiDEHMbKACLUmFWv8Q9tu"),
+            farm_token_mint: pubkey!("71vZ7Jvu8fTyFzpX399dmoSovoz24rVbipLrRn2wBNzW"),
+            global_farm_state: pubkey!("4RRRJkscV2DmwJUxTQgRdYock75GfwYJn7LTxy9rGTmY"),
+            global_reward_token_vault: pubkey!("H3ozvCeEwnsqnM2naCnXVxLLwH2XPC5kU8BH97XDpDwS"),
+            reward_token_authority: pubkey!("EavNUagNtD7DEdV4atcm3dEBXafARKCNJyNkyfz426m6"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("AUZaXYcFbpsgpGXhMdQ6hHr5fb9H6RkevrNxidd4Qmsa");
@@ -240,10 +460,31 @@ pub mod sol_usdt_orca {
     }
 }
 pub mod eth_sol_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("71FymgN2ZUf7VvVTLE8jYEnjP3jSK1Frp2XT1nHs8Hob");
     pub const POOL_ID: u8 = 23;
     pub const NAME: &str = "ETH_SOL_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::eth::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("EuK3xDa4rWuHeMQCBsHf1ETZNiEQb5C476oE9u9kp8Ji"),
+        swap_authority: pubkey!("DffrDbzPiswDJaiicBBo9CjqztKgFLrqXGwNJH4XQefZ"),
+        swap_token_a: pubkey!("7F2cLdio3i6CCJaypj9VfNDPW2DwT3vkDmZJDEfmxu6A"),
+        swap_token_b: pubkey!("5pUTGvN2AA2BEzBDU4CNDh3LHER15WS6J8oJf5XeZFD8"),
+        fee_account: pubkey!("unxKgWEc71ZiHwMqZs3VLqjcjmZhfTZEg94ZLGvjdMP"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("6ckhPnn6tCr88aq9SxhWaAA5G7izuXNKhVk1Xa62zhFD"),
+            farm_token_mint: pubkey!("CGFTRh4jKLPbS9r4hZtbDfaRuC7qcA8rZpbLnVTzJBer"),
+            global_farm_state: pubkey!("3ARgavt1NhqLmJWj3wAJy6XBarG6pJbEKRv1wzzRbbaN"),
+            global_reward_token_vault: pubkey!("FYTTVMqWPzbnhTsukgiWmPiNJam4yLTxHM9mpzdan2zo"),
+            reward_token_authority: pubkey!("HXY2Vvj2XyqiPNXV3PhM9YYKgfjqzXUX4tUFRnvqihdY"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("iZQwU7EYQiMz43WUmh3hGoFaeMcnP7BbgPZ3dSh7Ntn");
@@ -254,10 +495,31 @@ pub mod eth_sol_orca {
     }
 }
 pub mod apt_usdc_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("HNrYngS1eoqkjWro9D3Y5Z9sWBDzPNK2tX4rfV2Up177");
     pub const POOL_ID: u8 = 28;
     pub const NAME: &str = "APT_USDC_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::apt::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("Fg3UabVqnfycMtkiTVoaia9eNafehtT9Y4TicH2iBtvK"),
+        swap_authority: pubkey!("JDEYn1JsacdxoB4v4mbctFSVrSUPttacX3gxWphFHJKZ"),
+        swap_token_a: pubkey!("636crNdZTf46gFUKuedaBCZDBMLahf7KGud2LyTMskU5"),
+        swap_token_b: pubkey!("DGEYFkEHyiuHWtHeCGiQGn1JbkGHqYrNwaP44miRbgxu"),
+        fee_account: pubkey!("41H5mWwsZKewJeV4wWiNjQ3U4VYBnwqCpzvAWt86baHd"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("Ha7NSMkfjQt2pWF8JY5p89T38NpKdm5da4FR3sYednin"),
+            farm_token_mint: pubkey!("Dx7DYSuaBufhXyQG7155ePkLmHyn6w7WeKKtQB9zscZV"),
+            global_farm_state: pubkey!("3YZ5GYL625vWibn7d8hMdrMBawy9HGUyeTe4AoXoME1Q"),
+            global_reward_token_vault: pubkey!("HyCJbQkccvMwC5FHAYBMjQCKXEjDo9fbhBa5pj8sc2v5"),
+            reward_token_authority: pubkey!("53y344S5Cv32ViwajrHxnsgcmam7Mw2nydcRgJEkqdGd"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("3kyc7Pot3qzCb3esLEnjJiG2tu99pD6V3oWJrQLwc2P7");
@@ -268,10 +530,39 @@ pub mod apt_usdc_orca {
     }
 }
 pub mod btc_msol_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("8nKJ4z9FSw6wrVZKASqBiS9DS1CiNsRnqwCCKVQjqdkB");
     pub const POOL_ID: u8 = 24;
     pub const NAME: &str = "BTC_mSOL_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::btc::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::msol::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("8DRw5wQE1pyg6RB1UwypGNFgb2Pzp2hpyDDNwo76Lcc8"),
+        swap_authority: pubkey!("3X1aLdyvcQNc8TvBMPiucMsRCnGMBnGsjJHpZEyCf3pn"),
+        swap_token_a: pubkey!("6D3sxC6yEe84FUnF5Kpbgx6gN57N9poJCKAtrCeCWdJo"),
+        swap_token_b: pubkey!("EPoVJLhi9QtVPVo8n31M5k5Knvb48j8zbYyRrUbrHwC5"),
+        fee_account: pubkey!("AqiLHbUAy4UWWKGVVgbHsaUVCMg1zemNkgsYBPSirT92"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("75gpvckCXk49zTUwG8QrzUSP4NpWh3JXdyELBrnAhimL"),
+            farm_token_mint: pubkey!("DzpLz78wuwyFsQToin8iDv6YK6aBEymRqQq82swiFh7r"),
+            global_farm_state: pubkey!("GBrpFtiTabs14mc4Hi1RX9YiQY7res6JxrVfMTADfcQV"),
+            global_reward_token_vault: pubkey!("CNe5S831UP4YkumU7UsusTkf7uxJnAVdmPe6jhF51k4y"),
+            reward_token_authority: pubkey!("8sVCTztvytajkdczYEZVkSmuoRLjnMezwpT46L5w4RWR"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: Some(OrcaFarmInfo {
+            global_lp_vault: pubkey!("DuyHVLzsqg6SZeFNbpUWfJf67kvAXPWUdUGJYWJK5vTu"),
+            farm_token_mint: pubkey!("6uA1ADUJbvwYJZpzUn9z9LuyKoRVngBKcQTKdXsSivA8"),
+            global_farm_state: pubkey!("Cn7QNyosNQ8DyKEeMDPmtg66R7vKMXigcQ561kTkFD8E"),
+            global_reward_token_vault: pubkey!("Ea3FYh9RMJxwsyu3xS7BesLMtpX32DURohiEigG2iJCx"),
+            reward_token_authority: pubkey!("9Lg5wBjcYDgY8S2ZAEqjtXAQ4UdHuw65aP1WmmWss4QX"),
+            user_farm_state: user_farm_state_2nd::ID,
+            user_reward_token_account: reward_dd_account_2nd::ID,
+        }),
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("7cf9RpGCZSzQCrytoqysoQjN7w2tpeX86MmLBv6cAyrp");
@@ -294,10 +585,39 @@ pub mod btc_msol_orca {
     }
 }
 pub mod msol_usdc_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("8PSfyiTVwPb6Rr2iZ8F3kNpbg65BCfJM9v8LfB916r44");
     pub const POOL_ID: u8 = 25;
     pub const NAME: &str = "mSOL_USDC_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::msol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("Hme4Jnqhdz2jAPUMnS7jGE5zv6Y1ynqrUEhmUAWkXmzn"),
+        swap_authority: pubkey!("9Z7E42k46kxnBjAh8YGXDw3rRGwwxQUBYM7Ccrmwg6ZP"),
+        swap_token_a: pubkey!("GBa7G5f1FqAXEgByuHXsqsEdpyMjRgT9SNxZwmmnEJAY"),
+        swap_token_b: pubkey!("7hFgNawzzmpDM8TTVCKm8jykBrym8C3TQdb8TDAfAVkD"),
+        fee_account: pubkey!("3W3Skj2vQsNEMhGRQprFXQy3Q8ZbM6ojdgiDCokVPWno"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("8F6NCo1PiakW7m3eeEZvdxsjXF5bkLD3QZsTxaNg9jvv"),
+            farm_token_mint: pubkey!("5r3vDsNTGXXb9cGQfqyNuYD2bjhRPymGJBfDmKosR9Ev"),
+            global_farm_state: pubkey!("EvtMzreDMq1U8ytV5fEmfoWNfPhrjZ87za835GuRvZCc"),
+            global_reward_token_vault: pubkey!("A1enLcj9XmuVeYCQScEruwnfAz7ksQhbuGFUgvgeS1a6"),
+            reward_token_authority: pubkey!("9czgZkSxLFtxmvWSb1PEHmUyBuNpAUxj9XAcHKikYnzt"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: Some(OrcaFarmInfo {
+            global_lp_vault: pubkey!("CdbgqE5B9oADrSAWc51Mgw6c3B6nvYJ4c431rftpoVqZ"),
+            farm_token_mint: pubkey!("9y3QYM5mcaB8tU7oXRzAQnzHVa75P8riDuPievLp64cY"),
+            global_farm_state: pubkey!("5fhDMuGKRDPWVWXf7BBEwifRFrp6XwXctDQoG7UHGVt6"),
+            global_reward_token_vault: pubkey!("XbkV9HZpLdv3CjMUfoq4t8nkxR6UguHb4oP8aAKBGV2"),
+            reward_token_authority: pubkey!("FvXa954NiCqE2jAthxV5oVcuuPAJCggwYtAihYDRhVUw"),
+            user_farm_state: user_farm_state_2nd::ID,
+            user_reward_token_account: reward_dd_account_2nd::ID,
+        }),
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("DuHRmA6Dc9L9TsoxcfYFuuu4Gt9U89ogv61ewbhhbKRP");
@@ -320,10 +640,31 @@ pub mod msol_usdc_orca {
     }
 }
 pub mod stsol_ust_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("HTZd53fYwYQRyAjiaPsZy9Gf41gobFdqkF4oKe3XLi95");
     pub const POOL_ID: u8 = 38;
     pub const NAME: &str = "stSOL_UST_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::stsol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::ust::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("9F3J6RY7PTkDb3SUUpg725uXyCceBGCpZrtmYGJwgMwF"),
+        swap_authority: pubkey!("wJydc21tAMxYDif8uvy5rWNGWDFNZnPPmqCvegyZRod"),
+        swap_token_a: pubkey!("GFso9SAGakm8ZFa3rmuonuerbcQ8ZbACNZN7idkKR5nw"),
+        swap_token_b: pubkey!("EZ7pJskN2a4pDknrdkLzGDHpzjbfgdBj3Tt594K9HZbL"),
+        fee_account: pubkey!("5rCbmppxMBHwBjCkLUP6fireQ12cL8LRa26QRUimoxN6"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("EQBy5YqWkLxr1xx1CZh2dDdX57XR9Ata4jNJebtuR61h"),
+            farm_token_mint: pubkey!("3Q44iV4URXdbS4Tk1PGs5VdWQoCxHB7zdcdMnemo8jfH"),
+            global_farm_state: pubkey!("GxhewC22S6wsXT156yC9SARvDnijoc3YEYyLVcQFDUCx"),
+            global_reward_token_vault: pubkey!("FFjuVr4FYdRGkPwaQREVQWHsBFGwQ1LH6VkmVSJL5Vva"),
+            reward_token_authority: pubkey!("8za7LwVuYbP2PivS3KbeohpUkSmRqLdTDxhrVzsHyFZf"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("73q4YJSXm38cqDbcYL467fr7bGZxViVt9MuRNF14GFpi");
@@ -334,10 +675,31 @@ pub mod stsol_ust_orca {
     }
 }
 pub mod orca_wheth_orca {
-    use solana_program::declare_id;
+    use crate::lp::{OrcaFarmInfo, OrcaLpSwapInfo};
+    use solana_program::{declare_id, pubkey};
     declare_id!("GsfyYHkSgC3Ta6aWR9MjB2sxoBrkGGeR2tAwXbpphf3");
     pub const POOL_ID: u8 = 39;
     pub const NAME: &str = "ORCA_whETH_ORCA";
+    pub const LEFT_POOL_ID: u8 = super::orca::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::wheth::POOL_ID;
+    pub const SWAP_INFO: OrcaLpSwapInfo = OrcaLpSwapInfo {
+        lp_mint: ID,
+        swap: pubkey!("D67u6UEuFp9Q4Fu2gnu5thxBoVj2eRgDDDirdWsMVsgm"),
+        swap_authority: pubkey!("4kwchkCnCaZPkBdnk85j9bJ9F1P883rwjbvsXGV7H5rM"),
+        swap_token_a: pubkey!("EMxDw1NjdddQjPk9gCUN4iDMy8HJ5siKYMVjGbjuUCrh"),
+        swap_token_b: pubkey!("DQyhcuEvE1K9DSd51agcNLg4CDrgthnKMVhU7q6R55LV"),
+        fee_account: pubkey!("GdHXLai5M8FKedXUsECSzrV72nb1RTCCooFyTWT8WYmT"),
+        farm: OrcaFarmInfo {
+            global_lp_vault: pubkey!("CYSwKVGEaPazRXUcCo2Ve9wyovK44E9ftLNSsvVqRuf1"),
+            farm_token_mint: pubkey!("A7EAHvFEWzW8qSTFNLrEHN894GAZWZesNiebttEwtUGJ"),
+            global_farm_state: pubkey!("AgWH92f8cPAuURyqehF6A9wFgupEsnDX3VP5eHyytabA"),
+            global_reward_token_vault: pubkey!("DoaerVHonB3FYpzKtqADug9UtrPLzgY1gbzZm6cRuymi"),
+            reward_token_authority: pubkey!("B1pahxFpN3GX4TKsw7fEDLw5MuuzmHNUbVcPZ6bp7KBM"),
+            user_farm_state: user_farm_state::ID,
+            user_reward_token_account: reward_orca_account::ID,
+        },
+        double_dip: None,
+    };
     pub mod user_farm_state {
         use solana_program::declare_id;
         declare_id!("8xzKDQKm6N9ERkkx8J8azT3icsA1skfkuhxLfSdVvEcB");
@@ -349,10 +711,53 @@ pub mod orca_wheth_orca {
 }
 
 pub mod sol_usdc_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("8HoQnePLqPj4M7PUDzfw8e3Ymdwgc7NLGnaTUapubyvu");
     pub const POOL_ID: u8 = 9;
     pub const NAME: &str = "SOL_USDC_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("58oQChx4yWmvKdwLLZzBi4ChoCc2fqCUWBkwMihLYQo2"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("HRk9CMrpq7Jn9sh7mzxE8CChHG8dneX9p475QKz4Fsfc"),
+        amm_target_orders: pubkey!("CZza3Ej4Mc58MnxWA385itCC9jCo3L1D7zc3LKy1bZMR"),
+        pool_coin_token: pubkey!("DQyrAcCrDXQ7NeoqGgDCZwBvWDcYmFCjSb9JtteuvPpz"),
+        pool_pc_token: pubkey!("HLmqeL62xR1QoZ1HKKbXRrdN1p3phKpxRMb2VVopvBBz"),
+        pool_withdraw_queue: pubkey!("G7xeGGLevkRwB5f44QNgQtrPKBdMfkT6ZZwpS9xcC97n"),
+        pool_temp_lp_token: pubkey!("Awpt6N7ZYPBa4vG4BQNFhFxDj4sxExAA9rpBAoBw2uok"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("9wFFyRfZBsuAha4YcuxcXLKwMxJR43S7fPfQLusDBzvT"),
+        serum_coin_vault: pubkey!("36c6YqAwyGKQG66XEp2dJc5JqjaBNv7sVghEtJv4c7u6"),
+        serum_pc_vault: pubkey!("8CFo8bL8mZQK8abbFyypFMwEDd8tVJjHTTojMLgQTUSZ"),
+        serum_vault_signer: pubkey!("F8Vyqk3unwxkXukZFQeYyGmFfTG3CAX4v24iyrjEYBJV"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("5KKsLVU6TcbVDK4BS6K1DGDxnh4Q9xjYJ8XaDCG5t8ht"),
+            bids: pubkey!("14ivtgssEBoBjuZJtSAPKYgpUK7DmnSwuPMqJoVTSgKJ"),
+            asks: pubkey!("CEQdAFKdycHugujQg9k2wbmxjcpdYZyVLfV9WerTnafJ"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_v5_program::ID,
+            pool_id: pubkey!("GUzaohfNuFbBqQTnPgPSNciv3aUvriXYjQduRE3ZkqFw"),
+            pool_authority: pubkey!("DgbCWnbXg43nmeiAveMCkUUPEpAr3rZo3iop3TyP6S63"),
+            pool_lp_vault: pubkey!("J6ECnRDZEXcxuruvErXDWsPZn9czowKynUr9eDSQ4QeN"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[
+                RaydiumReward {
+                    user_reward_account: reward_ray_account::ID,
+                    reward_vault: pubkey!("38YS2N7VUb856QDsXHS1h8zv5556YgEy9zKbbL2mefjf"),
+                },
+                RaydiumReward {
+                    user_reward_account: reward_second_account::ID,
+                    reward_vault: pubkey!("ANDJUfDryy3jY6DngwGRXVyxCJBT5JfojLDXwZYSpnEL"),
+                },
+            ]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("44tSF4Sisrsy7YXmtSYnFLzQnZeVvwgd5PTMzRvAqtq4");
@@ -367,10 +772,47 @@ pub mod sol_usdc_raydium {
     }
 }
 pub mod ray_usdc_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("FbC6K13MzHvN42bXrtGaWsvZY9fxrackRSZcBGfjPc7m");
     pub const POOL_ID: u8 = 10;
     pub const NAME: &str = "RAY_USDC_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::ray::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("6UmmUiYoBjSrhakAobJw8BvkmJtDVxaeBtbt7rxWo1mg"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("J8u8nTHYtvudyqwLrXZboziN95LpaHFHpd97Jm5vtbkW"),
+        amm_target_orders: pubkey!("3cji8XW5uhtsA757vELVFAeJpskyHwbnTSceMFY5GjVT"),
+        pool_coin_token: pubkey!("FdmKUE4UMiJYFK5ogCngHzShuVKrFXBamPWcewDr31th"),
+        pool_pc_token: pubkey!("Eqrhxd7bDUCH3MepKmdVkgwazXRzY6iHhEoBpY7yAohk"),
+        pool_withdraw_queue: pubkey!("ERiPLHrxvjsoMuaWDWSTLdCMzRkQSo8SkLBLYEmSokyr"),
+        pool_temp_lp_token: pubkey!("D1V5GMf3N26owUFcbz2qR5N4G81qPKQvS2Vc4SM73XGB"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("2xiv8A5xrJ7RnGdxXB42uFEkYHJjszEhaJyKKt4WaLep"),
+        serum_coin_vault: pubkey!("GGcdamvNDYFhAXr93DWyJ8QmwawUHLCyRqWL3KngtLRa"),
+        serum_pc_vault: pubkey!("22jHt5WmosAykp3LPGSAKgY45p7VGh4DFWSwp21SWBVe"),
+        serum_vault_signer: pubkey!("FmhXe9uG6zun49p222xt3nG1rBAkWvzVz7dxERQ6ouGw"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("H9dZt8kvz1Fe5FyRisb77KcYTaN8LEbuVAfJSnAaEABz"),
+            bids: pubkey!("Hf84mYadE1VqSvVWAvCWc9wqLXak4RwXiPb4A91EAUn5"),
+            asks: pubkey!("DC1HsWWRCXVg3wk2NndS5LTbce3axwUwUZH1RgnV4oDN"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_program::ID,
+            pool_id: pubkey!("CHYrUBX2RKX8iBg7gYTkccoGNBzP44LdaazMHCLcdEgS"),
+            pool_authority: pubkey!("5KQFnDd33J5NaMC9hQ64P5XzaaSz8Pt7NBCkZFYn1po"),
+            pool_lp_vault: pubkey!("BNnXLFGva3K8ACruAc1gaP49NCbLkyE6xWhGV4G2HLrs"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[RaydiumReward {
+                user_reward_account: reward_ray_account::ID,
+                reward_vault: pubkey!("DpRueBHHhrQNvrjZX7CwGitJDJ8eZc3AHcyFMG4LqCQR"),
+            }]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("49i8NSa6z2DcWxBnnsZjyxKvLxEqXGZ833B4jUDNmxnT");
@@ -385,10 +827,53 @@ pub mod ray_usdc_raydium {
     }
 }
 pub mod sol_usdt_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("Epm4KfTj4DMrvqn6Bwg2Tr2N8vhQuNbuK8bESFp4k33K");
     pub const POOL_ID: u8 = 14;
     pub const NAME: &str = "SOL_USDT_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdt::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("7XawhbbxtsRcQA8KTkHT9f9nc6d69UwqCDh6U5EEbEmX"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("4NJVwEAoudfSvU5kdxKm5DsQe4AAqG6XxpZcNdQVinS4"),
+        amm_target_orders: pubkey!("9x4knb3nuNAzxsV7YFuGLgnYqKArGemY54r2vFExM1dp"),
+        pool_coin_token: pubkey!("876Z9waBygfzUrwwKFfnRcc7cfY4EQf6Kz1w7GRgbVYW"),
+        pool_pc_token: pubkey!("CB86HtaqpXbNWbq67L18y5x2RhqoJ6smb7xHUcyWdQAQ"),
+        pool_withdraw_queue: pubkey!("52AfgxYPTGruUA9XyE8eF46hdR6gMQiA6ShVoMMsC6jQ"),
+        pool_temp_lp_token: pubkey!("2JKZRQc92TaH3fgTcUZyxfD7k7V7BMqhF24eussPtkwh"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("HWHvQhFmJB3NUcu1aihKmrKegfVxBEHzwVX6yZCKEsi1"),
+        serum_coin_vault: pubkey!("29cTsXahEoEBwbHwVc59jToybFpagbBMV6Lh45pWEmiK"),
+        serum_pc_vault: pubkey!("EJwyNJJPbHH4pboWQf1NxegoypuY48umbfkhyfPew4E"),
+        serum_vault_signer: pubkey!("CzZAjoEqA6sjqtaiZiPqDkmxG6UuZWxwRWCenbBMc8Xz"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("GR363LDmwe25NZQMGtD2uvsiX66FzYByeQLcNFr596FK"),
+            bids: pubkey!("2juozaawVqhQHfYZ9HNcs66sPatFHSHeKG5LsTbrS2Dn"),
+            asks: pubkey!("ANXcuziKhxusxtthGxPxywY7FLRtmmCwFWDmU5eBDLdH"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_v5_program::ID,
+            pool_id: pubkey!("5r878BSWPtoXgnqaeFJi7BCycKZ5CodBB2vS9SeiV8q"),
+            pool_authority: pubkey!("DimG1WK9N7NdbhddweGTDDBRaBdCmcbPtoWZJ4Fi4rn4"),
+            pool_lp_vault: pubkey!("jfhZy3B6sqeu95z71GukkxpkDtfHXJiFAMULM6STWxb"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[
+                RaydiumReward {
+                    user_reward_account: reward_ray_account::ID,
+                    reward_vault: pubkey!("Bgj3meVYds8ficJc9xntbjmMBPVUuyn6CvDUm1AD39yq"),
+                },
+                RaydiumReward {
+                    user_reward_account: reward_second_account::ID,
+                    reward_vault: pubkey!("DJifNDjNt7iHbkNHs9V6Wm5pdiuddtF9w3o4WEiraKrP"),
+                },
+            ]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("4aryP8pemzEuJjMteEPHFbM1SJdgoahx4AG1ZpdCvJZQ");
@@ -403,16 +888,74 @@ pub mod sol_usdt_raydium {
     }
 }
 pub mod msol_sol_raydium {
-    use solana_program::declare_id;
+    use crate::lp::RaydiumLpSwapInfo;
+    use solana_program::{declare_id, pubkey};
     declare_id!("5ijRoAHVgd5T5CNtK5KDRUBZ7Bffb69nktMj5n6ks6m4");
     pub const POOL_ID: u8 = 22;
     pub const NAME: &str = "mSOL_SOL_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::msol::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("EGyhb2uLAsRUbRx9dNFBjMVYnFaASWMvD6RE1aEf2LxL"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("6c1u1cNEELKPmuH352WPNNEPdfTyVPHsei39DUPemC42"),
+        amm_target_orders: pubkey!("CLuMpSesLPqdxewQTxfiLdifQfDfRsxkFhPgiChmdGfk"),
+        pool_coin_token: pubkey!("85SxT7AdDQvJg6pZLoDf7vPiuXLj5UYZLVVNWD1NjnFK"),
+        pool_pc_token: pubkey!("BtGUR6y7uwJ6UGXNMcY3gCLm7dM3WaBdmgtKVgGnE1TJ"),
+        pool_withdraw_queue: pubkey!("7vvoHxA6di9EvzJKL6bmojbZnH3YaRXu2LitufrQhM21"),
+        pool_temp_lp_token: pubkey!("ACn8TZ27fQ85kgdPKUfkETB4dS5JPFoq53z7uCgtHDai"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("5cLrMai1DsLRYc1Nio9qMTicsWtvzjzZfJPXyAoF4t1Z"),
+        serum_coin_vault: pubkey!("2qmHPJn3URkrboLiJkQ5tBB4bmYWdb6MyhQzZ6ms7wf9"),
+        serum_pc_vault: pubkey!("A6eEM36Vpyti2PoHK8h8Dqk5zu7YTaSRTQb7XXL8tcrV"),
+        serum_vault_signer: pubkey!("EHMK3DdPiPBd9aBjeRU4aZjD7z568rmwHCSAAxRooPq6"),
+        serum_queues: None,
+        stake: None,
+    };
 }
 pub mod ray_usdt_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("C3sT1R3nsw4AVdepvLTLKr5Gvszr7jufyBWUCvy4TUvT");
     pub const POOL_ID: u8 = 31;
     pub const NAME: &str = "RAY_USDT_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::ray::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdt::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("DVa7Qmb5ct9RCpaU7UTpSaf3GVMYz17vNVU67XpdCRut"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("7UF3m8hDGZ6bNnHzaT2YHrhp7A7n9qFfBj6QEpHPv5S8"),
+        amm_target_orders: pubkey!("3K2uLkKwVVPvZuMhcQAPLF8hw95somMeNwJS7vgWYrsJ"),
+        pool_coin_token: pubkey!("3wqhzSB9avepM9xMteiZnbJw75zmTBDVmPFLTQAGcSMN"),
+        pool_pc_token: pubkey!("5GtSbKJEPaoumrDzNj4kGkgZtfDyUceKaHrPziazALC1"),
+        pool_withdraw_queue: pubkey!("8VuvrSWfQP8vdbuMAP9AkfgLxU9hbRR6BmTJ8Gfas9aK"),
+        pool_temp_lp_token: pubkey!("FBzqDD1cBgkZ1h6tiZNFpkh4sZyg6AG8K5P9DSuJoS5F"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("teE55QrL4a4QSfydR9dnHF97jgCfptpuigbb53Lo95g"),
+        serum_coin_vault: pubkey!("2kVNVEgHicvfwiyhT2T51YiQGMPFWLMSp8qXc1hHzkpU"),
+        serum_pc_vault: pubkey!("5AXZV7XfR7Ctr6yjQ9m9dbgycKeUXWnWqHwBTZT6mqC7"),
+        serum_vault_signer: pubkey!("HzWpBN6ucpsA9wcfmhLAFYqEUmHjE9n2cGHwunG5avpL"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("58KcficuUqPDcMittSddhT8LzsPJoH46YP4uURoMo5EB"),
+            bids: pubkey!("AvKStCiY8LTp3oDFrMkiHHxxhxk4sQUWnGVcetm4kRpy"),
+            asks: pubkey!("Hj9kckvMX96mQokfMBzNCYEYMLEBYKQ9WwSc1GxasW11"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_program::ID,
+            pool_id: pubkey!("AvbVWpBi2e4C9HPmZgShGdPoNydG4Yw8GJvG9HUcLgce"),
+            pool_authority: pubkey!("8JYVFy3pYsPSpPRsqf43KSJFnJzn83nnRLQgG88XKB8q"),
+            pool_lp_vault: pubkey!("4u4AnMBHXehdpP5tbD6qzB5Q4iZmvKKR5aUr2gavG7aw"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[RaydiumReward {
+                user_reward_account: reward_ray_account::ID,
+                reward_vault: pubkey!("HCHNuGzkqSnw9TbwpPv1gTnoqnqYepcojHw9DAToBrUj"),
+            }]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("3YUuGZJSF5Jdy3mXBXgWh86t2msj4d2WvNGawSsDZbHC");
@@ -427,10 +970,47 @@ pub mod ray_usdt_raydium {
     }
 }
 pub mod ray_eth_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("mjQH33MqZv5aKAbKHi8dG3g3qXeRQqq1GFcXceZkNSr");
     pub const POOL_ID: u8 = 32;
     pub const NAME: &str = "RAY_ETH_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::ray::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::eth::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("8iQFhWyceGREsWnLM8NkG9GC8DvZunGZyMzuyUScgkMK"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("7iztHknuo7FAXVrrpAjsHBEEjRTaNH4b3hecVApQnSwN"),
+        amm_target_orders: pubkey!("JChSqhn6yyEWqD95t8UR5DaZZtEZ1RGGjdwgMc8S6UUt"),
+        pool_coin_token: pubkey!("G3Szi8fUqxfZjZoNx17kQbxeMTyXt2ieRvju4f3eJt9j"),
+        pool_pc_token: pubkey!("7MgaPPNa7ySdu5XV7ik29Xoav4qcDk4wznXZ2Muq9MnT"),
+        pool_withdraw_queue: pubkey!("C9aijsE3tLbVyYaXXHi45qneDL5jfyN8befuJh8zzpou"),
+        pool_temp_lp_token: pubkey!("3CDnyBsNnexdvfvo6ASde5Q4e72jzMQFHRRkSQr49vEG"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("6jx6aoNFbmorwyncVP5V5ESKfuFc9oUYebob1iF6tgN4"),
+        serum_coin_vault: pubkey!("EVVtYo4AeCbmn2dYS1UnhtfjpzCXCcN26G1HmuHwMo7w"),
+        serum_pc_vault: pubkey!("6ZT6KwvjLnJLpFdVfiRD9ifVUo4gv4MUie7VvPTuk69v"),
+        serum_vault_signer: pubkey!("HXbRDLcX2FyqWJY95apnsTgBoRHyp7SWYXcMYod6EBrQ"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("EgcugBBSwM2FxqLQx5S6zAiU9x9qRS8qMVRMDFFU4Zty"),
+            bids: pubkey!("Hdvh4ZGL9MkiQApNqfZtdmd4jM6Sz8e9akCUuxxkYhb8"),
+            asks: pubkey!("7vWmTv9Mh8XbAxcduEqed2dLtro4N7hFroqch6mMxYKM"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_program::ID,
+            pool_id: pubkey!("B6fbnZZ7sbKHR18ffEDD5Nncgp54iKN1GbCgjTRdqhS1"),
+            pool_authority: pubkey!("6amoZ7YBbsz3uUUbkeEH4vDTNwjvgjxTiu6nGi9z1JGe"),
+            pool_lp_vault: pubkey!("BjAfXpHTHz2kipraNddS6WwQvGGtbvyobn7MxLEEYfrH"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[RaydiumReward {
+                user_reward_account: reward_ray_account::ID,
+                reward_vault: pubkey!("7YfTgYQFGEJ4kb8jCF8cBrrUwEFskLin3EbvE1crqiQh"),
+            }]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("5PzDUuUYWmkymdNznZmvWAj5nn89xwFbD844rMJveHY3");
@@ -445,10 +1025,47 @@ pub mod ray_eth_raydium {
     }
 }
 pub mod ray_sol_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo, SerumQueues};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("89ZKE4aoyfLBe2RuV6jM3JGNhaV18Nxh8eNtjRcndBip");
     pub const POOL_ID: u8 = 33;
     pub const NAME: &str = "RAY_SOL_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::ray::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::sol::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("AVs9TA4nWDzfPJE9gGVNJMVhcQy3V9PGazuz33BfG2RA"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("6Su6Ea97dBxecd5W92KcVvv6SzCurE2BXGgFe9LNGMpE"),
+        amm_target_orders: pubkey!("5hATcCfvhVwAjNExvrg8rRkXmYyksHhVajWLa46iRsmE"),
+        pool_coin_token: pubkey!("Em6rHi68trYgBFyJ5261A2nhwuQWfLcirgzZZYoRcrkX"),
+        pool_pc_token: pubkey!("3mEFzHsJyu2Cpjrz6zPmTzP7uoLFj9SbbecGVzzkL1mJ"),
+        pool_withdraw_queue: pubkey!("FSHqX232PHE4ev9Dpdzrg9h2Tn1byChnX4tuoPUyjjdV"),
+        pool_temp_lp_token: pubkey!("87CCkBfthmyqwPuCDwFmyqKWJfjYqPFhm5btkNyoALYZ"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("C6tp2RVZnxBPFbnAsfTjis8BN9tycESAT4SgDQgbbrsA"),
+        serum_coin_vault: pubkey!("6U6U59zmFWrPSzm9sLX7kVkaK78Kz7XJYkrhP1DjF3uF"),
+        serum_pc_vault: pubkey!("4YEx21yeUAZxUL9Fs7YU9Gm3u45GWoPFs8vcJiHga2eQ"),
+        serum_vault_signer: pubkey!("7SdieGqwPJo5rMmSQM9JmntSEMoimM4dQn7NkGbNFcrd"),
+        serum_queues: Some(SerumQueues {
+            event_queue: pubkey!("4HGvdannxvmAhszVVig9auH6HsqVH17qoavDiNcnm9nj"),
+            bids: pubkey!("C1nEbACFaHMUiKAUsXVYPWZsuxunJeBkqXHPFr8QgSj9"),
+            asks: pubkey!("4DNBdnTw6wmrK4NmdSTTxs1kEz47yjqLGuoqsMeHvkMF"),
+        }),
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_program::ID,
+            pool_id: pubkey!("HUDr9BDaAGqi37xbQHzxCyXvfMCKPTPNF8g9c9bPu1Fu"),
+            pool_authority: pubkey!("9VbmvaaPeNAke2MAL3h2Fw82VubH1tBCzwBzaWybGKiG"),
+            pool_lp_vault: pubkey!("A4xQv2BQPB1WxsjiCC7tcMH7zUq255uCBkevFj8qSCyJ"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[RaydiumReward {
+                user_reward_account: reward_ray_account::ID,
+                reward_vault: pubkey!("6zA5RAQYgazm4dniS8AigjGFtRi4xneqjL7ehrSqCmhr"),
+            }]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("ChJUMQNtVNznGWaFUeNAqKD95hd1gmz9CRHobw3aMRbm");
@@ -463,10 +1080,49 @@ pub mod ray_sol_raydium {
     }
 }
 pub mod srm_usdc_raydium {
-    use solana_program::declare_id;
+    use crate::consts;
+    use crate::lp::{RaydiumLpSwapInfo, RaydiumReward, RaydiumStakeInfo};
+    use solana_program::{declare_id, pubkey};
+    use std::borrow::Cow;
     declare_id!("9XnZd82j34KxNLgQfz29jGbYdxsYznTWRpvZE3SRE7JG");
     pub const POOL_ID: u8 = 34;
     pub const NAME: &str = "SRM_USDC_RAYDIUM";
+    pub const LEFT_POOL_ID: u8 = super::srm::POOL_ID;
+    pub const RIGHT_POOL_ID: u8 = super::usdc::POOL_ID;
+    pub const SWAP_INFO: RaydiumLpSwapInfo = RaydiumLpSwapInfo {
+        lp_mint: ID,
+        amm_id: pubkey!("8tzS7SkUZyHPQY7gLqsMCXZ5EDCgjESUHcB17tiR1h3Z"),
+        amm_authority: pubkey!("5Q544fKrFoe6tsEbD7S8EmxGTJYAKtTVhAW5Q5pge4j1"),
+        amm_open_orders: pubkey!("GJwrRrNeeQKY2eGzuXGc3KBrBftYbidCYhmA6AZj2Zur"),
+        amm_target_orders: pubkey!("26LLpo8rscCpMxyAnJsqhqESPnzjMGiFdmXA4eF2Jrk5"),
+        pool_coin_token: pubkey!("zuLDJ5SEe76L3bpFp2Sm9qTTe5vpJL3gdQFT5At5xXG"),
+        pool_pc_token: pubkey!("4usvfgPDwXBX2ySX11ubTvJ3pvJHbGEW2ytpDGCSv5cw"),
+        pool_withdraw_queue: pubkey!("7c1VbXTB7Xqx5eQQeUxAu5o6GHPq3P1ByhDsnRRUWYxB"),
+        pool_temp_lp_token: pubkey!("2sozAi6zXDUCCkpgG3usphzeCDm4e2jTFngbm5atSdC9"),
+        serum_program: pubkey!("9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin"),
+        serum_market: pubkey!("ByRys5tuUWDgL73G8JBAEfkdFf8JWBzPBDHsBVQ5vbQA"),
+        serum_coin_vault: pubkey!("Ecfy8et9Mft9Dkavnuh4mzHMa2KWYUbBTA5oDZNoWu84"),
+        serum_pc_vault: pubkey!("hUgoKy5wjeFbZrXDW4ecr42T4F5Z1Tos31g68s5EHbP"),
+        serum_vault_signer: pubkey!("GVV4ZT9pccwy9d17STafFDuiSqFbXuRTdvKQ1zJX6ttX"),
+        serum_queues: None,
+        stake: Some(RaydiumStakeInfo {
+            stake_program: consts::raydium_stake_v5_program::ID,
+            pool_id: pubkey!("27bysJaX5eu5Urb5kftR66otiVc6DKK7TnifKwnpNzYu"),
+            pool_authority: pubkey!("HAWwtFc4MFNSXFyQbUZd2GefSwZLntCiumt1D6XM8jfk"),
+            pool_lp_vault: pubkey!("HVEm5BG4jMHtwgrUtuiC9K17bjp9CjFpgqmzVABmzLxr"),
+            user_ledger: user_ledger_account::ID,
+            rewards: Cow::Borrowed(&[
+                RaydiumReward {
+                    user_reward_account: reward_ray_account::ID,
+                    reward_vault: pubkey!("9gs6XnKs3RMMSSQAZm3VCbRpoNmPMrGaQQGMmRKjPeSU"),
+                },
+                RaydiumReward {
+                    user_reward_account: reward_second_account::ID,
+                    reward_vault: pubkey!("BsuQ3XCCapopam8byEzHzazyxcRn5dCT3UX9kUzozhw"),
+                },
+            ]),
+        }),
+    };
     pub mod reward_ray_account {
         use solana_program::declare_id;
         declare_id!("2qgtUtNopD3ZCrQCbVsvYd1BrPeWxn4TcrXjwvTzLCYi");
@@ -481,138 +1137,558 @@ pub mod srm_usdc_raydium {
     }
 }
 
-pub fn get_pool_id_by_name(token_name: &str) -> u8 {
-    match token_name {
-        apt::NAME => apt::POOL_ID,
-        btc::NAME => btc::POOL_ID,
-        eth::NAME => eth::POOL_ID,
-        wheth::NAME => wheth::POOL_ID,
-        sol::NAME => sol::POOL_ID,
-        msol::NAME => msol::POOL_ID,
-        stsol::NAME => stsol::POOL_ID,
-        scnsol::NAME => scnsol::POOL_ID,
-        ray::NAME => ray::POOL_ID,
-        orca::NAME => orca::POOL_ID,
-        srm::NAME => srm::POOL_ID,
-        usdt::NAME => usdt::POOL_ID,
-        usdc::NAME => usdc::POOL_ID,
-        ust::NAME => ust::POOL_ID,
-        usdt_usdc_saber::NAME => usdt_usdc_saber::POOL_ID,
-        msol_sol_saber::NAME => msol_sol_saber::POOL_ID,
-        stsol_sol_saber::NAME => stsol_sol_saber::POOL_ID,
-        ust_usdc_saber::NAME => ust_usdc_saber::POOL_ID,
-        usdc_usdt_orca::NAME => usdc_usdt_orca::POOL_ID,
-        sol_usdc_orca::NAME => sol_usdc_orca::POOL_ID,
-        msol_sol_orca::NAME => msol_sol_orca::POOL_ID,
-        orca_usdc_orca::NAME => orca_usdc_orca::POOL_ID,
-        orca_sol_orca::NAME => orca_sol_orca::POOL_ID,
-        eth_usdc_orca::NAME => eth_usdc_orca::POOL_ID,
-        sol_usdt_orca::NAME => sol_usdt_orca::POOL_ID,
-        eth_sol_orca::NAME => eth_sol_orca::POOL_ID,
-        apt_usdc_orca::NAME => apt_usdc_orca::POOL_ID,
-        btc_msol_orca::NAME => btc_msol_orca::POOL_ID,
-        msol_usdc_orca::NAME => msol_usdc_orca::POOL_ID,
-        stsol_ust_orca::NAME => stsol_ust_orca::POOL_ID,
-        orca_wheth_orca::NAME => orca_wheth_orca::POOL_ID,
-        sol_usdc_raydium::NAME => sol_usdc_raydium::POOL_ID,
-        ray_usdc_raydium::NAME => ray_usdc_raydium::POOL_ID,
-        sol_usdt_raydium::NAME => sol_usdt_raydium::POOL_ID,
-        msol_sol_raydium::NAME => msol_sol_raydium::POOL_ID,
-        ray_usdt_raydium::NAME => ray_usdt_raydium::POOL_ID,
-        ray_eth_raydium::NAME => ray_eth_raydium::POOL_ID,
-        ray_sol_raydium::NAME => ray_sol_raydium::POOL_ID,
-        srm_usdc_raydium::NAME => srm_usdc_raydium::POOL_ID,
-        _ => panic!("Token doens't have a pool!"),
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PoolCategory {
+    Volatile,
+    Stable,
+    Lp,
+}
+
+// everything the client knows about a pool
+#[derive(Clone, Debug, PartialEq)]
+pub struct PoolConfig {
+    pub name: String,
+    pub pool_id: u8,
+    pub mint: Pubkey,
+    pub decimals: u8,
+    pub category: PoolCategory,
+    // discount liquidators get on this pool's token when it is seized as collateral
+    pub liquidation_discount: f64,
+    // LP legs, DEX and farm accounts, only for LP pools
+    pub lp: Option<LpInfo>,
+}
+
+impl PoolConfig {
+    pub fn decimal_multiplier(&self) -> u64 {
+        10u64.pow(self.decimals as u32)
+    }
+
+    pub fn is_lp(&self) -> bool {
+        self.lp.is_some()
+    }
+
+    // (left_pool_id, right_pool_id)
+    pub fn lp_legs(&self) -> Option<(u8, u8)> {
+        self.lp
+            .as_ref()
+            .map(|lp| (lp.left_pool_id, lp.right_pool_id))
+    }
+
+    pub fn dex(&self) -> Option<SwapTarget> {
+        self.lp.as_ref().map(|lp| lp.swap_info.target_swap())
+    }
+
+    pub fn farm_keys(&self) -> Option<Vec<AccountMeta>> {
+        self.lp.as_ref().map(|lp| lp.swap_info.stake_keys())
+    }
+
+    pub fn second_farm_keys(&self) -> Option<Vec<AccountMeta>> {
+        self.lp
+            .as_ref()
+            .and_then(|lp| lp.swap_info.second_stake_keys())
     }
 }
 
-pub fn get_mint_by_pool_id(pool_id: u8) -> Pubkey {
-    match pool_id {
-        apt::POOL_ID => apt::ID,
-        btc::POOL_ID => btc::ID,
-        eth::POOL_ID => eth::ID,
-        wheth::POOL_ID => wheth::ID,
-        sol::POOL_ID => sol::ID,
-        msol::POOL_ID => msol::ID,
-        stsol::POOL_ID => stsol::ID,
-        scnsol::POOL_ID => scnsol::ID,
-        ray::POOL_ID => ray::ID,
-        orca::POOL_ID => orca::ID,
-        srm::POOL_ID => srm::ID,
-        usdt::POOL_ID => usdt::ID,
-        usdc::POOL_ID => usdc::ID,
-        ust::POOL_ID => ust::ID,
-        usdt_usdc_saber::POOL_ID => usdt_usdc_saber::ID,
-        msol_sol_saber::POOL_ID => msol_sol_saber::ID,
-        stsol_sol_saber::POOL_ID => stsol_sol_saber::ID,
-        ust_usdc_saber::POOL_ID => ust_usdc_saber::ID,
-        usdc_usdt_orca::POOL_ID => usdc_usdt_orca::ID,
-        sol_usdc_orca::POOL_ID => sol_usdc_orca::ID,
-        msol_sol_orca::POOL_ID => msol_sol_orca::ID,
-        orca_usdc_orca::POOL_ID => orca_usdc_orca::ID,
-        orca_sol_orca::POOL_ID => orca_sol_orca::ID,
-        eth_usdc_orca::POOL_ID => eth_usdc_orca::ID,
-        sol_usdt_orca::POOL_ID => sol_usdt_orca::ID,
-        eth_sol_orca::POOL_ID => eth_sol_orca::ID,
-        apt_usdc_orca::POOL_ID => apt_usdc_orca::ID,
-        btc_msol_orca::POOL_ID => btc_msol_orca::ID,
-        msol_usdc_orca::POOL_ID => msol_usdc_orca::ID,
-        stsol_ust_orca::POOL_ID => stsol_ust_orca::ID,
-        orca_wheth_orca::POOL_ID => orca_wheth_orca::ID,
-        sol_usdc_raydium::POOL_ID => sol_usdc_raydium::ID,
-        ray_usdc_raydium::POOL_ID => ray_usdc_raydium::ID,
-        sol_usdt_raydium::POOL_ID => sol_usdt_raydium::ID,
-        msol_sol_raydium::POOL_ID => msol_sol_raydium::ID,
-        ray_usdt_raydium::POOL_ID => ray_usdt_raydium::ID,
-        ray_eth_raydium::POOL_ID => ray_eth_raydium::ID,
-        ray_sol_raydium::POOL_ID => ray_sol_raydium::ID,
-        srm_usdc_raydium::POOL_ID => srm_usdc_raydium::ID,
-        _ => panic!("Token doens't have a pool!"),
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum RegistryError {
+    DuplicatePoolId { pool_id: u8 },
+    // an LP pool's leg isn't in the registry
+    MissingLpLeg { pool_id: u8, leg_pool_id: u8 },
+    // a Raydium farm needs at least the RAY reward, stake keys start with it
+    NoRaydiumRewards { pool_id: u8 },
+}
+
+impl fmt::Display for RegistryError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RegistryError::DuplicatePoolId { pool_id } => {
+                write!(f, "pool {} is listed more than once", pool_id)
+            }
+            RegistryError::MissingLpLeg {
+                pool_id,
+                leg_pool_id,
+            } => write!(
+                f,
+                "LP pool {} has leg {} which isn't listed",
+                pool_id, leg_pool_id
+            ),
+            RegistryError::NoRaydiumRewards { pool_id } => {
+                write!(f, "Raydium LP pool {} has a farm without rewards", pool_id)
+            }
+        }
     }
 }
 
-pub fn get_pool_id_by_token_mint(token_mint: Pubkey) -> u8 {
-    if token_mint == apt::ID {
-        return apt::POOL_ID;
+impl std::error::Error for RegistryError {}
+
+// table of pools with lookups in every direction
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PoolRegistry {
+    pools: Vec<PoolConfig>,
+}
+
+impl PoolRegistry {
+    // pool_ids must be unique, LP legs must be pools of the registry and Raydium farms need a
+    // reward
+    pub fn new(pools: Vec<PoolConfig>) -> Result<Self, RegistryError> {
+        for (i, pool) in pools.iter().enumerate() {
+            if pools[..i].iter().any(|other| other.pool_id == pool.pool_id) {
+                return Err(RegistryError::DuplicatePoolId {
+                    pool_id: pool.pool_id,
+                });
+            }
+            if let Some((left, right)) = pool.lp_legs() {
+                for leg_pool_id in [left, right].iter().copied() {
+                    if !pools.iter().any(|other| other.pool_id == leg_pool_id) {
+                        return Err(RegistryError::MissingLpLeg {
+                            pool_id: pool.pool_id,
+                            leg_pool_id,
+                        });
+                    }
+                }
+            }
+            if let Some(LpSwapInfo::Raydium(info)) = pool.lp.as_ref().map(|lp| &lp.swap_info) {
+                if matches!(&info.stake, Some(stake) if stake.rewards.is_empty()) {
+                    return Err(RegistryError::NoRaydiumRewards {
+                        pool_id: pool.pool_id,
+                    });
+                }
+            }
+        }
+        Ok(PoolRegistry { pools })
     }
-    if token_mint == btc::ID {
-        return btc::POOL_ID;
+
+    pub fn mainnet() -> Self {
+        // the table is checked by config_test
+        PoolRegistry::new(mainnet_pools()).expect("mainnet pools are consistent")
     }
-    if token_mint == eth::ID {
-        return eth::POOL_ID;
+
+    pub fn pools(&self) -> &[PoolConfig] {
+        &self.pools
     }
-    if token_mint == wheth::ID {
-        return wheth::POOL_ID;
+
+    pub fn iter(&self) -> impl Iterator<Item = &PoolConfig> {
+        self.pools.iter()
     }
-    if token_mint == sol::ID {
-        return sol::POOL_ID;
+
+    pub fn by_pool_id(&self, pool_id: u8) -> Option<&PoolConfig> {
+        self.pools.iter().find(|pool| pool.pool_id == pool_id)
     }
-    if token_mint == msol::ID {
-        return msol::POOL_ID;
+
+    pub fn by_name(&self, name: &str) -> Option<&PoolConfig> {
+        self.pools.iter().find(|pool| pool.name == name)
     }
-    if token_mint == stsol::ID {
-        return stsol::POOL_ID;
+
+    pub fn by_mint(&self, mint: &Pubkey) -> Option<&PoolConfig> {
+        self.pools.iter().find(|pool| pool.mint == *mint)
     }
-    if token_mint == scnsol::ID {
-        return scnsol::POOL_ID;
+
+    // None for pools that are not LP pools
+    pub fn lp_info(&self, pool_id: u8) -> Option<&LpInfo> {
+        self.by_pool_id(pool_id).and_then(|pool| pool.lp.as_ref())
     }
-    if token_mint == ray::ID {
-        return ray::POOL_ID;
+
+    // accounts of a margin swap between the two legs of lp_pool_id through its DEX, None unless
+    // sell_pool_id and buy_pool_id are its legs
+    pub fn swap_accounts(
+        &self,
+        lp_pool_id: u8,
+        sell_pool_id: u8,
+        buy_pool_id: u8,
+    ) -> Option<SwapAccounts> {
+        let lp = self.lp_info(lp_pool_id)?;
+        let legs = (lp.left_pool_id, lp.right_pool_id);
+        let sell_left = if legs == (sell_pool_id, buy_pool_id) {
+            true
+        } else if legs == (buy_pool_id, sell_pool_id) {
+            false
+        } else {
+            return None;
+        };
+        lp.swap_info.swap_accounts(sell_left)
     }
-    if token_mint == orca::ID {
-        return orca::POOL_ID;
+
+    pub fn lp_pools(&self) -> impl Iterator<Item = &PoolConfig> {
+        self.pools.iter().filter(|pool| pool.is_lp())
     }
-    if token_mint == srm::ID {
-        return srm::POOL_ID;
+
+    // LP pools with pool_id as one of their legs
+    pub fn lp_pools_of(&self, pool_id: u8) -> impl Iterator<Item = &PoolConfig> {
+        self.lp_pools().filter(move |pool| {
+            matches!(pool.lp_legs(), Some((left, right)) if left == pool_id || right == pool_id)
+        })
     }
-    if token_mint == usdt::ID {
-        return usdt::POOL_ID;
+}
+
+// program and global account addresses of a deployment, every other account of it is derived
+// from these. The convenience builders in instructions and ApricotEnv share one implementation
+// that takes them.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DeploymentKeys {
+    pub program_id: Pubkey,
+    pub base_pda: Pubkey,
+    pub price_pda: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub user_pages_stats: Pubkey,
+    pub lm_apt_vault: Pubkey,
+}
+
+impl DeploymentKeys {
+    pub const MAINNET: DeploymentKeys = DeploymentKeys {
+        program_id: consts::program::ID,
+        base_pda: consts::base_pda::ID,
+        price_pda: consts::price_pda::ID,
+        pool_summaries: consts::pool_summaries::ID,
+        price_summaries: consts::price_summaries::ID,
+        user_pages_stats: consts::user_stats::ID,
+        lm_apt_vault: consts::lm_apt_vault::ID,
+    };
+
+    pub fn pool_list_k(&self) -> Pubkey {
+        self.program_k(&self.base_pda, consts::POOL_LIST_SEED)
+    }
+
+    pub fn users_page_k(&self, page_id: u16) -> Pubkey {
+        let seed = format!("UsersPage_{}", page_id);
+        self.program_k(&self.base_pda, &seed)
+    }
+
+    pub fn asset_pool_k(&self, pool_id: u8) -> Pubkey {
+        let mut mint_seed_buffer = [0u8; 8];
+        let pool_seed_str = consts::pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+        self.program_k(&self.base_pda, pool_seed_str)
+    }
+
+    pub fn asset_pool_spl_k(&self, token_program_id: &Pubkey, pool_id: u8) -> Pubkey {
+        let mut mint_seed_buffer = [0u8; 8];
+        let pool_seed_str = consts::pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+        Pubkey::create_with_seed(&self.base_pda, pool_seed_str, token_program_id).unwrap()
+    }
+
+    pub fn asset_price_k(&self, pool_id: u8) -> Pubkey {
+        let mut mint_seed_buffer = [0u8; 8];
+        let pool_seed_str = consts::pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+        self.program_k(&self.price_pda, pool_seed_str)
+    }
+
+    pub fn user_info_k(&self, user_wallet_key: &Pubkey) -> Pubkey {
+        self.program_k(user_wallet_key, consts::USER_INFO_SEED)
+    }
+
+    pub fn asset_pool_stake_table_k(&self, pool_id: u8) -> Pubkey {
+        let mut stake_seed_buffer = [0u8; 8];
+        let stake_seed_str =
+            consts::pool_id_to_stake_table_seed_str(pool_id, &mut stake_seed_buffer);
+        self.program_k(&self.base_pda, stake_seed_str)
+    }
+
+    fn program_k(&self, base: &Pubkey, seed: &str) -> Pubkey {
+        Pubkey::create_with_seed(base, seed, &self.program_id).unwrap()
+    }
+}
+
+// program and global account addresses together with the pools, see loader for reading them
+// from a file
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApricotConfig {
+    pub program_id: Pubkey,
+    pub base_pda: Pubkey,
+    pub price_pda: Pubkey,
+    pub pool_summaries: Pubkey,
+    pub price_summaries: Pubkey,
+    pub user_pages_stats: Pubkey,
+    pub lm_apt_vault: Pubkey,
+    pub pools: PoolRegistry,
+}
+
+impl ApricotConfig {
+    pub fn new(keys: DeploymentKeys, pools: PoolRegistry) -> Self {
+        ApricotConfig {
+            program_id: keys.program_id,
+            base_pda: keys.base_pda,
+            price_pda: keys.price_pda,
+            pool_summaries: keys.pool_summaries,
+            price_summaries: keys.price_summaries,
+            user_pages_stats: keys.user_pages_stats,
+            lm_apt_vault: keys.lm_apt_vault,
+            pools,
+        }
+    }
+
+    pub fn mainnet() -> Self {
+        ApricotConfig::new(DeploymentKeys::MAINNET, PoolRegistry::mainnet())
+    }
+
+    pub fn keys(&self) -> DeploymentKeys {
+        DeploymentKeys {
+            program_id: self.program_id,
+            base_pda: self.base_pda,
+            price_pda: self.price_pda,
+            pool_summaries: self.pool_summaries,
+            price_summaries: self.price_summaries,
+            user_pages_stats: self.user_pages_stats,
+            lm_apt_vault: self.lm_apt_vault,
+        }
+    }
+
+    // addresses of a deployment of the program at program_id, e.g. on a local validator.
+    // pool_summaries and user_pages_stats are seeded from the admin key that initialized it.
+    pub fn derive(
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        lm_apt_vault: &Pubkey,
+        pools: PoolRegistry,
+    ) -> Self {
+        let (base_pda, _) = Pubkey::find_program_address(&[consts::BASE_PDA_SEED], program_id);
+        let (price_pda, _) = Pubkey::find_program_address(&[consts::PRICE_PDA_SEED], program_id);
+        let with_seed =
+            |base: &Pubkey, seed: &str| Pubkey::create_with_seed(base, seed, program_id).unwrap();
+        ApricotConfig {
+            program_id: *program_id,
+            base_pda,
+            price_pda,
+            pool_summaries: with_seed(admin, consts::POOL_SUMMARIES_SEED),
+            price_summaries: with_seed(&base_pda, consts::PRICE_SUMMARIES_SEED),
+            user_pages_stats: with_seed(admin, consts::USER_PAGES_STATS_SEED),
+            lm_apt_vault: *lm_apt_vault,
+            pools,
+        }
+    }
+}
+
+impl Default for ApricotConfig {
+    fn default() -> Self {
+        ApricotConfig::mainnet()
+    }
+}
+
+fn token(
+    name: &str,
+    pool_id: u8,
+    mint: Pubkey,
+    decimals: u8,
+    category: PoolCategory,
+    liquidation_discount: f64,
+) -> PoolConfig {
+    PoolConfig {
+        name: name.to_string(),
+        pool_id,
+        mint,
+        decimals,
+        category,
+        liquidation_discount,
+        lp: None,
+    }
+}
+
+fn lp(
+    name: &str,
+    pool_id: u8,
+    mint: Pubkey,
+    decimals: u8,
+    left_pool_id: u8,
+    right_pool_id: u8,
+    swap_info: LpSwapInfo,
+) -> PoolConfig {
+    PoolConfig {
+        lp: Some(LpInfo {
+            lp_pool_id: pool_id,
+            left_pool_id,
+            right_pool_id,
+            swap_info,
+        }),
+        ..token(name, pool_id, mint, decimals, PoolCategory::Lp, 0.0)
+    }
+}
+
+#[rustfmt::skip]
+fn mainnet_pools() -> Vec<PoolConfig> {
+    vec![
+        token(apt::NAME, apt::POOL_ID, apt::ID, 6, PoolCategory::Volatile, 0.0),
+        token(btc::NAME, btc::POOL_ID, btc::ID, 6, PoolCategory::Volatile, 0.04),
+        token(eth::NAME, eth::POOL_ID, eth::ID, 6, PoolCategory::Volatile, 0.04),
+        token(wheth::NAME, wheth::POOL_ID, wheth::ID, 8, PoolCategory::Volatile, 0.04),
+        token(sol::NAME, sol::POOL_ID, sol::ID, 9, PoolCategory::Volatile, 0.04),
+        token(msol::NAME, msol::POOL_ID, msol::ID, 9, PoolCategory::Volatile, 0.04),
+        token(stsol::NAME, stsol::POOL_ID, stsol::ID, 9, PoolCategory::Volatile, 0.04),
+        token(scnsol::NAME, scnsol::POOL_ID, scnsol::ID, 9, PoolCategory::Volatile, 0.04),
+        token(ray::NAME, ray::POOL_ID, ray::ID, 6, PoolCategory::Volatile, 0.04),
+        token(orca::NAME, orca::POOL_ID, orca::ID, 6, PoolCategory::Volatile, 0.04),
+        token(srm::NAME, srm::POOL_ID, srm::ID, 6, PoolCategory::Volatile, 0.04),
+        token(usdt::NAME, usdt::POOL_ID, usdt::ID, 6, PoolCategory::Stable, 0.04),
+        token(usdc::NAME, usdc::POOL_ID, usdc::ID, 6, PoolCategory::Stable, 0.04),
+        token(ust::NAME, ust::POOL_ID, ust::ID, 6, PoolCategory::Stable, 0.04),
+        lp(usdt_usdc_saber::NAME, usdt_usdc_saber::POOL_ID, usdt_usdc_saber::ID, 6, usdt_usdc_saber::LEFT_POOL_ID, usdt_usdc_saber::RIGHT_POOL_ID, LpSwapInfo::Saber(usdt_usdc_saber::SWAP_INFO)),
+        lp(msol_sol_saber::NAME, msol_sol_saber::POOL_ID, msol_sol_saber::ID, 9, msol_sol_saber::LEFT_POOL_ID, msol_sol_saber::RIGHT_POOL_ID, LpSwapInfo::Saber(msol_sol_saber::SWAP_INFO)),
+        lp(stsol_sol_saber::NAME, stsol_sol_saber::POOL_ID, stsol_sol_saber::ID, 9, stsol_sol_saber::LEFT_POOL_ID, stsol_sol_saber::RIGHT_POOL_ID, LpSwapInfo::Saber(stsol_sol_saber::SWAP_INFO)),
+        lp(ust_usdc_saber::NAME, ust_usdc_saber::POOL_ID, ust_usdc_saber::ID, 6, ust_usdc_saber::LEFT_POOL_ID, ust_usdc_saber::RIGHT_POOL_ID, LpSwapInfo::Saber(ust_usdc_saber::SWAP_INFO)),
+        lp(usdc_usdt_orca::NAME, usdc_usdt_orca::POOL_ID, usdc_usdt_orca::ID, 6, usdc_usdt_orca::LEFT_POOL_ID, usdc_usdt_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(usdc_usdt_orca::SWAP_INFO)),
+        lp(sol_usdc_orca::NAME, sol_usdc_orca::POOL_ID, sol_usdc_orca::ID, 6, sol_usdc_orca::LEFT_POOL_ID, sol_usdc_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(sol_usdc_orca::SWAP_INFO)),
+        lp(msol_sol_orca::NAME, msol_sol_orca::POOL_ID, msol_sol_orca::ID, 6, msol_sol_orca::LEFT_POOL_ID, msol_sol_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(msol_sol_orca::SWAP_INFO)),
+        lp(orca_usdc_orca::NAME, orca_usdc_orca::POOL_ID, orca_usdc_orca::ID, 6, orca_usdc_orca::LEFT_POOL_ID, orca_usdc_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(orca_usdc_orca::SWAP_INFO)),
+        lp(orca_sol_orca::NAME, orca_sol_orca::POOL_ID, orca_sol_orca::ID, 6, orca_sol_orca::LEFT_POOL_ID, orca_sol_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(orca_sol_orca::SWAP_INFO)),
+        lp(eth_usdc_orca::NAME, eth_usdc_orca::POOL_ID, eth_usdc_orca::ID, 6, eth_usdc_orca::LEFT_POOL_ID, eth_usdc_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(eth_usdc_orca::SWAP_INFO)),
+        lp(sol_usdt_orca::NAME, sol_usdt_orca::POOL_ID, sol_usdt_orca::ID, 6, sol_usdt_orca::LEFT_POOL_ID, sol_usdt_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(sol_usdt_orca::SWAP_INFO)),
+        lp(eth_sol_orca::NAME, eth_sol_orca::POOL_ID, eth_sol_orca::ID, 6, eth_sol_orca::LEFT_POOL_ID, eth_sol_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(eth_sol_orca::SWAP_INFO)),
+        lp(apt_usdc_orca::NAME, apt_usdc_orca::POOL_ID, apt_usdc_orca::ID, 6, apt_usdc_orca::LEFT_POOL_ID, apt_usdc_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(apt_usdc_orca::SWAP_INFO)),
+        lp(btc_msol_orca::NAME, btc_msol_orca::POOL_ID, btc_msol_orca::ID, 6, btc_msol_orca::LEFT_POOL_ID, btc_msol_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(btc_msol_orca::SWAP_INFO)),
+        lp(msol_usdc_orca::NAME, msol_usdc_orca::POOL_ID, msol_usdc_orca::ID, 6, msol_usdc_orca::LEFT_POOL_ID, msol_usdc_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(msol_usdc_orca::SWAP_INFO)),
+        lp(stsol_ust_orca::NAME, stsol_ust_orca::POOL_ID, stsol_ust_orca::ID, 6, stsol_ust_orca::LEFT_POOL_ID, stsol_ust_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(stsol_ust_orca::SWAP_INFO)),
+        lp(orca_wheth_orca::NAME, orca_wheth_orca::POOL_ID, orca_wheth_orca::ID, 6, orca_wheth_orca::LEFT_POOL_ID, orca_wheth_orca::RIGHT_POOL_ID, LpSwapInfo::Orca(orca_wheth_orca::SWAP_INFO)),
+        lp(sol_usdc_raydium::NAME, sol_usdc_raydium::POOL_ID, sol_usdc_raydium::ID, 9, sol_usdc_raydium::LEFT_POOL_ID, sol_usdc_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(sol_usdc_raydium::SWAP_INFO)),
+        lp(ray_usdc_raydium::NAME, ray_usdc_raydium::POOL_ID, ray_usdc_raydium::ID, 6, ray_usdc_raydium::LEFT_POOL_ID, ray_usdc_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(ray_usdc_raydium::SWAP_INFO)),
+        lp(sol_usdt_raydium::NAME, sol_usdt_raydium::POOL_ID, sol_usdt_raydium::ID, 9, sol_usdt_raydium::LEFT_POOL_ID, sol_usdt_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(sol_usdt_raydium::SWAP_INFO)),
+        lp(msol_sol_raydium::NAME, msol_sol_raydium::POOL_ID, msol_sol_raydium::ID, 9, msol_sol_raydium::LEFT_POOL_ID, msol_sol_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(msol_sol_raydium::SWAP_INFO)),
+        lp(ray_usdt_raydium::NAME, ray_usdt_raydium::POOL_ID, ray_usdt_raydium::ID, 6, ray_usdt_raydium::LEFT_POOL_ID, ray_usdt_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(ray_usdt_raydium::SWAP_INFO)),
+        lp(ray_eth_raydium::NAME, ray_eth_raydium::POOL_ID, ray_eth_raydium::ID, 6, ray_eth_raydium::LEFT_POOL_ID, ray_eth_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(ray_eth_raydium::SWAP_INFO)),
+        lp(ray_sol_raydium::NAME, ray_sol_raydium::POOL_ID, ray_sol_raydium::ID, 6, ray_sol_raydium::LEFT_POOL_ID, ray_sol_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(ray_sol_raydium::SWAP_INFO)),
+        lp(srm_usdc_raydium::NAME, srm_usdc_raydium::POOL_ID, srm_usdc_raydium::ID, 6, srm_usdc_raydium::LEFT_POOL_ID, srm_usdc_raydium::RIGHT_POOL_ID, LpSwapInfo::Raydium(srm_usdc_raydium::SWAP_INFO)),
+    ]
+}
+
+#[deprecated(note = "panics on unknown tokens, use PoolRegistry::by_name")]
+pub fn get_pool_id_by_name(token_name: &str) -> u8 {
+    match PoolRegistry::mainnet().by_name(token_name) {
+        Some(pool) => pool.pool_id,
+        None => panic!("Token doesn't have a pool!"),
+    }
+}
+
+#[deprecated(note = "panics on unknown pools, use PoolRegistry::by_pool_id")]
+pub fn get_mint_by_pool_id(pool_id: u8) -> Pubkey {
+    match PoolRegistry::mainnet().by_pool_id(pool_id) {
+        Some(pool) => pool.mint,
+        None => panic!("Token doesn't have a pool!"),
     }
-    if token_mint == usdc::ID {
-        return usdc::POOL_ID;
+}
+
+// None for pools that are not LP pools
+pub fn get_lp_info(pool_id: u8) -> Option<LpInfo> {
+    PoolRegistry::mainnet().lp_info(pool_id).cloned()
+}
+
+#[deprecated(note = "panics on unknown mints, use PoolRegistry::by_mint")]
+pub fn get_pool_id_by_token_mint(token_mint: Pubkey) -> u8 {
+    match PoolRegistry::mainnet().by_mint(&token_mint) {
+        Some(pool) => pool.pool_id,
+        None => panic!("Token doesn't have a pool!"),
     }
-    if token_mint == ust::ID {
-        return ust::POOL_ID;
+}
+
+#[cfg(test)]
+pub mod config_test {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_pool_registry() {
+        let registry = PoolRegistry::mainnet();
+        assert_eq!(registry.pools().len(), 39);
+        for pool in registry.iter() {
+            assert_eq!(registry.by_pool_id(pool.pool_id), Some(pool));
+            assert_eq!(registry.by_name(&pool.name), Some(pool));
+            assert_eq!(registry.by_mint(&pool.mint), Some(pool));
+            if let Some((left, right)) = pool.lp_legs() {
+                assert!(registry.by_pool_id(left).is_some());
+                assert!(registry.by_pool_id(right).is_some());
+            }
+        }
+        assert_eq!(registry.by_pool_id(7), None);
+        // the legacy lookups read the same table
+        assert_eq!(get_pool_id_by_name(usdc::NAME), usdc::POOL_ID);
+        assert_eq!(get_mint_by_pool_id(usdc::POOL_ID), usdc::ID);
+        assert_eq!(
+            get_pool_id_by_token_mint(msol_sol_orca::ID),
+            msol_sol_orca::POOL_ID
+        );
+        assert!(get_lp_info(usdc::POOL_ID).is_none());
+
+        let msol = registry.by_pool_id(msol::POOL_ID).unwrap();
+        assert_eq!(msol.decimal_multiplier(), 1_000_000_000);
+        assert_eq!(registry.lp_pools_of(msol::POOL_ID).count(), 5);
+        let double_dip = registry.by_name(msol_sol_orca::NAME).unwrap();
+        assert_eq!(double_dip.dex(), Some(SwapTarget::Orca));
+        assert!(double_dip.second_farm_keys().is_some());
+    }
+
+    #[test]
+    fn test_pool_registry_validation() {
+        let mut pools = mainnet_pools();
+        pools.push(pools[0].clone());
+        assert_eq!(
+            PoolRegistry::new(pools),
+            Err(RegistryError::DuplicatePoolId {
+                pool_id: mainnet_pools()[0].pool_id
+            })
+        );
+        let pools = mainnet_pools()
+            .into_iter()
+            .filter(|pool| pool.pool_id != usdc::POOL_ID)
+            .collect();
+        assert!(matches!(
+            PoolRegistry::new(pools),
+            Err(RegistryError::MissingLpLeg {
+                leg_pool_id: usdc::POOL_ID,
+                ..
+            })
+        ));
+        let mut pools = mainnet_pools();
+        let raydium = pools
+            .iter_mut()
+            .find(|pool| pool.dex() == Some(SwapTarget::Raydium))
+            .unwrap();
+        let pool_id = raydium.pool_id;
+        if let Some(LpSwapInfo::Raydium(info)) = raydium.lp.as_mut().map(|lp| &mut lp.swap_info) {
+            info.stake.as_mut().unwrap().rewards = vec![].into();
+        }
+        assert_eq!(
+            PoolRegistry::new(pools),
+            Err(RegistryError::NoRaydiumRewards { pool_id })
+        );
+    }
+
+    #[test]
+    fn test_swap_accounts() {
+        let registry = PoolRegistry::mainnet();
+        // USDT is the left leg but token b of the saber swap
+        let info = usdt_usdc_saber::SWAP_INFO;
+        let accounts =
+            registry.swap_accounts(usdt_usdc_saber::POOL_ID, usdt::POOL_ID, usdc::POOL_ID);
+        match accounts {
+            Some(SwapAccounts::Saber(accounts)) => {
+                assert_eq!(accounts.source_vault, info.token_b_vault);
+                assert_eq!(accounts.destination_vault, info.token_a_vault);
+                assert_eq!(accounts.admin_fee_destination, info.token_a_fees);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(
+            registry
+                .swap_accounts(sol_usdc_orca::POOL_ID, sol::POOL_ID, usdc::POOL_ID)
+                .map(|accounts| accounts.target_swap()),
+            Some(SwapTarget::Orca)
+        );
+        assert_eq!(
+            registry.swap_accounts(sol_usdc_orca::POOL_ID, sol::POOL_ID, usdt::POOL_ID),
+            None
+        );
+        assert_eq!(
+            registry.swap_accounts(usdc::POOL_ID, sol::POOL_ID, usdc::POOL_ID),
+            None
+        );
+    }
+
+    #[test]
+    fn test_derive_matches_mainnet() {
+        let admin = Pubkey::from_str("6L2QoTpr8WUd76eLAGnvow8i3WQzRP36C1qdUna9iwMn").unwrap();
+        let derived = ApricotConfig::derive(
+            &consts::program::ID,
+            &admin,
+            &consts::lm_apt_vault::ID,
+            PoolRegistry::mainnet(),
+        );
+        assert_eq!(derived, ApricotConfig::mainnet());
     }
-    panic!("Token doens't have a pool!")
 }
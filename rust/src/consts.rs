@@ -38,6 +38,53 @@ pub mod lm_apt_vault {
     declare_id!("C1k4CehboSgUkmL3BJfw32Xj9HPs9NKTzhT5WXsYwWh4");
 }
 
+// programs of the swaps LP pools are created on
+pub mod saber_swap_program {
+    use solana_program::declare_id;
+    declare_id!("SSwpkEEcbUqx4vtoEByFjSkhKdCT862DNVb52nZg1UZ");
+}
+
+pub mod orca_swap_program {
+    use solana_program::declare_id;
+    declare_id!("9W959DqEETiGZocYWCQPaJ6sBmUzgfxXfqGeTEdp3aQP");
+}
+
+pub mod raydium_amm_program {
+    use solana_program::declare_id;
+    declare_id!("675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8");
+}
+
+pub mod mercurial_swap_program {
+    use solana_program::declare_id;
+    declare_id!("MERLuDFBMmsHnsBPZw2sDQZHvXFMwp8EdjudcU2HKky");
+}
+
+// programs of the farms LP token is staked into
+pub mod saber_quarry_program {
+    use solana_program::declare_id;
+    declare_id!("QMNeHCGYnLVDn1icRAfQZpjPLBNkfGbSKRB83G5d8KB");
+}
+
+pub mod orca_farm_program {
+    use solana_program::declare_id;
+    declare_id!("82yxjeMsvaURa4MbZZ7WZZHfobirZYkH1zF8fmeGtyaQ");
+}
+
+pub mod raydium_stake_program {
+    use solana_program::declare_id;
+    declare_id!("EhhTKczWMGQt46ynNeRX1WfeagwwJd7ufHvCDjRxjo5Q");
+}
+
+pub mod raydium_stake_v5_program {
+    use solana_program::declare_id;
+    declare_id!("9KEPoZmtHUrBbhWN1v1KWLMkkvwY6WLtAVUCPRtRjP4z");
+}
+
+pub mod associated_token_program {
+    use solana_program::declare_id;
+    declare_id!("ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL");
+}
+
 // commands
 
 pub const CMD_REFRESH_USER: u8 = 0x0a;
@@ -52,8 +99,24 @@ pub const CMD_UPDATE_USER_CONFIG: u8 = 0x17;
 pub const CMD_MARGIN_SWAP: u8 = 0x18;
 pub const CMD_UPDATE_USER_ASSET_CONFIG: u8 = 0x19;
 pub const CMD_WITHDRAW_AND_REMOVE_USER: u8 = 0x1a;
+pub const CMD_LP_CREATE: u8 = 0x1d;
+pub const CMD_LP_REDEEM: u8 = 0x1e;
+pub const CMD_LP_OP_CHECK: u8 = 0x21;
+pub const CMD_LP_OP_ENDCHECK: u8 = 0x22;
+pub const CMD_LP_STAKE: u8 = 0x23;
+pub const CMD_LP_UNSTAKE: u8 = 0x24;
 pub const CMD_CLAIM_APT_LM_REWARD: u8 = 0x29;
 pub const CMD_MAKE_LM_REWARD_AVAILABLE: u8 = 0x2a;
+pub const CMD_LP_STAKE_SECOND: u8 = 0x81;
+pub const CMD_LP_UNSTAKE_SECOND: u8 = 0x82;
+
+// swap identifiers, used as target_swap in margin swap
+pub const SWAP_FAKE: u8 = 0x00;
+pub const SWAP_SERUM: u8 = 0x01;
+pub const SWAP_RAYDIUM: u8 = 0x02;
+pub const SWAP_SABER: u8 = 0x03;
+pub const SWAP_MERCURIAL: u8 = 0x04;
+pub const SWAP_ORCA: u8 = 0x05;
 
 // errors
 pub const ERR_INCORRECT_BASE_PDA: u32 = 0x1000;
@@ -127,23 +190,37 @@ pub const ERR_ASSIST_CHECK_MUST_BE_FIRST: u32 = 0x4019;
 pub const ERR_ASSIST_ACTION_NOT_ALLOWED: u32 = 0x4020;
 pub const ERR_ASSIST_INCONSISTENT_ACTION: u32 = 0x4021;
 
-pub const USER_INFO_SEED: &'static str = "UserInfo";
-pub const USER_PAGES_STATS_SEED: &'static str = "UserPagesStats";
+pub const USER_INFO_SEED: &str = "UserInfo";
+pub const USER_PAGES_STATS_SEED: &str = "UserPagesStats";
+pub const POOL_LIST_SEED: &str = "PoolList";
+pub const POOL_SUMMARIES_SEED: &str = "PoolSummaries";
+pub const PRICE_SUMMARIES_SEED: &str = "PriceSummaries";
+// program address seeds of base_pda and price_pda
+pub const BASE_PDA_SEED: &[u8] = b"2";
+pub const PRICE_PDA_SEED: &[u8] = b"PRICE";
 
 // Address calculation
 #[inline(always)]
 pub fn bytes_to_str(key_bytes: &[u8]) -> &str {
-    unsafe { std::mem::transmute(key_bytes) }
+    std::str::from_utf8(key_bytes).unwrap()
 }
 
 #[inline(always)]
-pub fn pool_id_to_seed_str<'a>(pool_id: u8, buffer: &'a mut [u8; 8]) -> &'a str {
+pub fn pool_id_to_seed_str(pool_id: u8, buffer: &mut [u8; 8]) -> &str {
     *buffer = *b"POOL____";
     buffer[6] = (pool_id / 16) + b'a';
     buffer[7] = (pool_id % 16) + b'a';
     bytes_to_str(buffer)
 }
 
+#[inline(always)]
+pub fn pool_id_to_stake_table_seed_str(pool_id: u8, buffer: &mut [u8; 8]) -> &str {
+    *buffer = *b"STAK____";
+    buffer[6] = (pool_id / 16) + b'a';
+    buffer[7] = (pool_id % 16) + b'a';
+    bytes_to_str(buffer)
+}
+
 #[inline(always)]
 pub fn get_base_pda() -> Pubkey {
     base_pda::ID
@@ -159,6 +236,11 @@ pub fn get_price_summaries_k() -> Pubkey {
     price_summaries::ID
 }
 
+#[inline(always)]
+pub fn get_pool_list_k() -> Pubkey {
+    Pubkey::create_with_seed(&base_pda::ID, POOL_LIST_SEED, &program::ID).unwrap()
+}
+
 #[inline(always)]
 pub fn get_user_pages_stats_k() -> Pubkey {
     user_stats::ID
@@ -170,21 +252,50 @@ pub fn get_users_page_k(page_id: u16) -> Pubkey {
     Pubkey::create_with_seed(&base_pda::ID, &seed, &program::ID).unwrap()
 }
 
+// (page_id, address) of every users page
+pub fn users_page_keys() -> impl Iterator<Item = (u16, Pubkey)> {
+    (0..crate::utils::NUM_PAGES as u16).map(|page_id| (page_id, get_users_page_k(page_id)))
+}
+
 #[inline(always)]
 pub fn get_asset_pool_k(pool_id: u8) -> Pubkey {
-    let mut mint_seed_buffer = [0 as u8; 8];
+    let mut mint_seed_buffer = [0u8; 8];
     let pool_seed_str = pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
     Pubkey::create_with_seed(&base_pda::ID, pool_seed_str, &program::ID).unwrap()
 }
 
 #[inline(always)]
 pub fn get_asset_pool_spl_k(token_program_id: &Pubkey, pool_id: u8) -> Pubkey {
-    let mut mint_seed_buffer = [0 as u8; 8];
+    let mut mint_seed_buffer = [0u8; 8];
     let pool_seed_str = pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
     Pubkey::create_with_seed(&base_pda::ID, pool_seed_str, token_program_id).unwrap()
 }
 
+#[inline(always)]
+pub fn get_asset_price_k(pool_id: u8) -> Pubkey {
+    let mut mint_seed_buffer = [0u8; 8];
+    let pool_seed_str = pool_id_to_seed_str(pool_id, &mut mint_seed_buffer);
+    Pubkey::create_with_seed(&price_pda::ID, pool_seed_str, &program::ID).unwrap()
+}
+
 #[inline(always)]
 pub fn get_user_info_k(user_wallet_key: &Pubkey) -> Pubkey {
     Pubkey::create_with_seed(user_wallet_key, USER_INFO_SEED, &program::ID).unwrap()
 }
+
+// per LP pool record of how much LP token base_pda has staked
+#[inline(always)]
+pub fn get_asset_pool_stake_table_k(pool_id: u8) -> Pubkey {
+    let mut stake_seed_buffer = [0u8; 8];
+    let stake_seed_str = pool_id_to_stake_table_seed_str(pool_id, &mut stake_seed_buffer);
+    Pubkey::create_with_seed(&base_pda::ID, stake_seed_str, &program::ID).unwrap()
+}
+
+#[inline(always)]
+pub fn get_associated_token_k(owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[owner.as_ref(), spl_token::ID.as_ref(), mint.as_ref()],
+        &associated_token_program::ID,
+    )
+    .0
+}
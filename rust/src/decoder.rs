@@ -0,0 +1,503 @@
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use std::fmt;
+
+use crate::config::DeploymentKeys;
+use crate::consts;
+use crate::instructions::*;
+use crate::utils::{self, Pod};
+
+// Inverse of the builders in instructions.rs. Accounts are picked by the position the builders
+// put them at, venue specific accounts (swap, farm) are not decoded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ApricotInstruction {
+    RefreshUser {
+        user_wallet: Pubkey,
+    },
+    AddUserAndDeposit {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        amount: u64,
+        pool_id: u8,
+        page_id: u16,
+    },
+    Deposit {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        amount: u64,
+        pool_id: u8,
+    },
+    Withdraw {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        amount: u64,
+        pool_id: u8,
+    },
+    WithdrawAndRemoveUser {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        users_page: Pubkey,
+        amount: u64,
+        pool_id: u8,
+    },
+    Borrow {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        amount: u64,
+        pool_id: u8,
+    },
+    Repay {
+        user_wallet: Pubkey,
+        user_spl: Pubkey,
+        repay_all: bool,
+        amount: u64,
+        pool_id: u8,
+    },
+    ExternLiquidate {
+        liquidated_wallet: Pubkey,
+        liquidator_wallet: Pubkey,
+        repaid_borrow_amount: u64,
+        min_collateral_amount: u64,
+        borrowed_pool_id: u8,
+        collateral_pool_id: u8,
+    },
+    UpdateUserConfig {
+        user_wallet: Pubkey,
+        self_liquidation_threshold: u8,
+        post_self_liquidation_ratio_target: u8,
+        post_extern_liquidation_ratio_target: u8,
+    },
+    MarginSwap {
+        user_wallet: Pubkey,
+        target_swap: SwapTarget,
+        is_buy: bool,
+        sell_pool_id: u8,
+        sell_amount: u64,
+        buy_pool_id: u8,
+        buy_amount: u64,
+    },
+    UpdateUserAssetConfig {
+        user_wallet: Pubkey,
+        use_as_collateral: bool,
+        pool_id: u8,
+    },
+    LpCreate {
+        user_wallet: Pubkey,
+        target_swap: SwapTarget,
+        left_pool_id: u8,
+        left_amount: u64,
+        right_pool_id: u8,
+        right_amount: u64,
+        lp_pool_id: u8,
+        min_lp_amount: u64,
+    },
+    LpRedeem {
+        user_wallet: Pubkey,
+        target_swap: SwapTarget,
+        left_pool_id: u8,
+        min_left_amount: u64,
+        right_pool_id: u8,
+        min_right_amount: u64,
+        lp_pool_id: u8,
+        lp_amount: u64,
+    },
+    // the check accounts are passed through from the caller, see instructions::lp_op_check
+    LpOpCheck,
+    LpOpEndcheck,
+    ClaimAptLmReward {
+        user_wallet: Pubkey,
+        user_apt_spl: Pubkey,
+    },
+    MakeLmRewardAvailable {
+        user_wallet: Pubkey,
+    },
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum InstructionDecodeError {
+    WrongProgram(Pubkey),
+    EmptyData,
+    UnknownCommand(u8),
+    WrongDataSize {
+        cmd: u8,
+        expected: usize,
+        actual: usize,
+    },
+    MissingAccounts {
+        cmd: u8,
+        expected: usize,
+        actual: usize,
+    },
+    InvalidSwapTarget(u8),
+}
+
+impl fmt::Display for InstructionDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            InstructionDecodeError::WrongProgram(program_id) => {
+                write!(f, "not an apricot instruction, program {}", program_id)
+            }
+            InstructionDecodeError::EmptyData => write!(f, "instruction data is empty"),
+            InstructionDecodeError::UnknownCommand(cmd) => {
+                write!(f, "unknown command {:#04x}", cmd)
+            }
+            InstructionDecodeError::WrongDataSize {
+                cmd,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "command {:#04x} expects {} bytes of data, got {}",
+                cmd, expected, actual
+            ),
+            InstructionDecodeError::MissingAccounts {
+                cmd,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "command {:#04x} expects at least {} accounts, got {}",
+                cmd, expected, actual
+            ),
+            InstructionDecodeError::InvalidSwapTarget(target_swap) => {
+                write!(f, "invalid target_swap {}", target_swap)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InstructionDecodeError {}
+
+impl ApricotInstruction {
+    pub fn from_instruction(ix: &Instruction) -> Result<Self, InstructionDecodeError> {
+        ApricotInstruction::from_instruction_in(&DeploymentKeys::MAINNET, ix)
+    }
+
+    // ix as sent to the deployment of keys, see ApricotEnv::decode_instruction
+    pub fn from_instruction_in(
+        keys: &DeploymentKeys,
+        ix: &Instruction,
+    ) -> Result<Self, InstructionDecodeError> {
+        if ix.program_id != keys.program_id {
+            return Err(InstructionDecodeError::WrongProgram(ix.program_id));
+        }
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        decode_instruction(&ix.data, &keys)
+    }
+}
+
+// checks data is exactly cmd + P and returns P
+fn param<P: Pod>(cmd: u8, data: &[u8]) -> Result<&P, InstructionDecodeError> {
+    let expected = 1 + std::mem::size_of::<P>();
+    if data.len() != expected {
+        return Err(InstructionDecodeError::WrongDataSize {
+            cmd,
+            expected,
+            actual: data.len(),
+        });
+    }
+    Ok(utils::cast::<P>(&data[1..]))
+}
+
+fn check_accounts(cmd: u8, keys: &[Pubkey], expected: usize) -> Result<(), InstructionDecodeError> {
+    if keys.len() < expected {
+        return Err(InstructionDecodeError::MissingAccounts {
+            cmd,
+            expected,
+            actual: keys.len(),
+        });
+    }
+    Ok(())
+}
+
+fn swap_target(target_swap: u8) -> Result<SwapTarget, InstructionDecodeError> {
+    SwapTarget::from_u8(target_swap).ok_or(InstructionDecodeError::InvalidSwapTarget(target_swap))
+}
+
+// data and account keys of an instruction sent to an Apricot deployment, e.g. from a historical
+// transaction
+pub fn decode_instruction(
+    data: &[u8],
+    keys: &[Pubkey],
+) -> Result<ApricotInstruction, InstructionDecodeError> {
+    let cmd = *data.first().ok_or(InstructionDecodeError::EmptyData)?;
+    let decoded = match cmd {
+        consts::CMD_REFRESH_USER => {
+            param::<()>(cmd, data)?;
+            check_accounts(cmd, keys, 3)?;
+            ApricotInstruction::RefreshUser {
+                user_wallet: keys[0],
+            }
+        }
+        consts::CMD_ADD_USER_AND_DEPOSIT => {
+            let p = param::<AddUserAndDepositParam>(cmd, data)?;
+            check_accounts(cmd, keys, 11)?;
+            ApricotInstruction::AddUserAndDeposit {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                amount: p.amount,
+                pool_id: p.pool_id,
+                page_id: p.page_id,
+            }
+        }
+        consts::CMD_DEPOSIT => {
+            let p = param::<DepositParam>(cmd, data)?;
+            check_accounts(cmd, keys, 8)?;
+            ApricotInstruction::Deposit {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                amount: p.amount,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_WITHDRAW => {
+            let p = param::<WithdrawParam>(cmd, data)?;
+            check_accounts(cmd, keys, 9)?;
+            ApricotInstruction::Withdraw {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                amount: p.amount,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_WITHDRAW_AND_REMOVE_USER => {
+            let p = param::<WithdrawParam>(cmd, data)?;
+            check_accounts(cmd, keys, 11)?;
+            ApricotInstruction::WithdrawAndRemoveUser {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                users_page: keys[3],
+                amount: p.amount,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_BORROW => {
+            let p = param::<BorrowParam>(cmd, data)?;
+            check_accounts(cmd, keys, 9)?;
+            ApricotInstruction::Borrow {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                amount: p.amount,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_REPAY => {
+            let p = param::<RepayParam>(cmd, data)?;
+            check_accounts(cmd, keys, 7)?;
+            ApricotInstruction::Repay {
+                user_wallet: keys[0],
+                user_spl: keys[1],
+                repay_all: p.repay_all != 0,
+                amount: p.amount,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_EXTERN_LIQUIDATE => {
+            let p = param::<ExternLiquidateParam>(cmd, data)?;
+            check_accounts(cmd, keys, 12)?;
+            ApricotInstruction::ExternLiquidate {
+                liquidated_wallet: keys[0],
+                liquidator_wallet: keys[1],
+                repaid_borrow_amount: p.repaid_borrow_amount,
+                min_collateral_amount: p.min_collateral_amount,
+                borrowed_pool_id: p.borrowed_pool_id,
+                collateral_pool_id: p.collateral_pool_id,
+            }
+        }
+        consts::CMD_UPDATE_USER_CONFIG => {
+            let p = param::<UpdateUserConfigParam>(cmd, data)?;
+            check_accounts(cmd, keys, 2)?;
+            ApricotInstruction::UpdateUserConfig {
+                user_wallet: keys[0],
+                self_liquidation_threshold: p.self_liquidation_threshold,
+                post_self_liquidation_ratio_target: p.post_self_liquidation_ratio_target,
+                post_extern_liquidation_ratio_target: p.post_extern_liquidation_ratio_target,
+            }
+        }
+        consts::CMD_MARGIN_SWAP => {
+            let p = param::<MarginSwapParam>(cmd, data)?;
+            check_accounts(cmd, keys, 10)?;
+            ApricotInstruction::MarginSwap {
+                user_wallet: keys[0],
+                target_swap: swap_target(p.target_swap)?,
+                is_buy: p.is_buy != 0,
+                sell_pool_id: p.sell_pool_id,
+                sell_amount: p.sell_amount,
+                buy_pool_id: p.buy_pool_id,
+                buy_amount: p.buy_amount,
+            }
+        }
+        consts::CMD_UPDATE_USER_ASSET_CONFIG => {
+            let p = param::<UpdateUserAssetConfigParam>(cmd, data)?;
+            check_accounts(cmd, keys, 4)?;
+            ApricotInstruction::UpdateUserAssetConfig {
+                user_wallet: keys[0],
+                use_as_collateral: p.use_as_collateral != 0,
+                pool_id: p.pool_id,
+            }
+        }
+        consts::CMD_LP_CREATE => {
+            let p = param::<LpCreateParam>(cmd, data)?;
+            check_accounts(cmd, keys, 12)?;
+            ApricotInstruction::LpCreate {
+                user_wallet: keys[0],
+                target_swap: swap_target(p.target_swap)?,
+                left_pool_id: p.left_pool_id,
+                left_amount: p.left_amount,
+                right_pool_id: p.right_pool_id,
+                right_amount: p.right_amount,
+                lp_pool_id: p.lp_pool_id,
+                min_lp_amount: p.min_lp_amount,
+            }
+        }
+        consts::CMD_LP_REDEEM => {
+            let p = param::<LpRedeemParam>(cmd, data)?;
+            check_accounts(cmd, keys, 12)?;
+            ApricotInstruction::LpRedeem {
+                user_wallet: keys[0],
+                target_swap: swap_target(p.target_swap)?,
+                left_pool_id: p.left_pool_id,
+                min_left_amount: p.min_left_amount,
+                right_pool_id: p.right_pool_id,
+                min_right_amount: p.min_right_amount,
+                lp_pool_id: p.lp_pool_id,
+                lp_amount: p.lp_amount,
+            }
+        }
+        consts::CMD_LP_OP_CHECK | consts::CMD_LP_OP_ENDCHECK => {
+            param::<()>(cmd, data)?;
+            if cmd == consts::CMD_LP_OP_CHECK {
+                ApricotInstruction::LpOpCheck
+            } else {
+                ApricotInstruction::LpOpEndcheck
+            }
+        }
+        consts::CMD_CLAIM_APT_LM_REWARD => {
+            param::<()>(cmd, data)?;
+            check_accounts(cmd, keys, 8)?;
+            ApricotInstruction::ClaimAptLmReward {
+                user_wallet: keys[1],
+                user_apt_spl: keys[3],
+            }
+        }
+        consts::CMD_MAKE_LM_REWARD_AVAILABLE => {
+            param::<()>(cmd, data)?;
+            check_accounts(cmd, keys, 4)?;
+            ApricotInstruction::MakeLmRewardAvailable {
+                user_wallet: keys[0],
+            }
+        }
+        _ => return Err(InstructionDecodeError::UnknownCommand(cmd)),
+    };
+    Ok(decoded)
+}
+
+#[cfg(test)]
+pub mod decoder_test {
+    use super::*;
+    use crate::config::{self, PoolRegistry};
+
+    #[test]
+    fn test_decode_round_trip() {
+        let user_wallet = Pubkey::new_unique();
+        let user_spl = Pubkey::new_unique();
+
+        let ix = deposit(&user_wallet, &user_spl, 1_000, 4);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Ok(ApricotInstruction::Deposit {
+                user_wallet,
+                user_spl,
+                amount: 1_000,
+                pool_id: 4
+            })
+        );
+
+        let ix = withdraw(&user_wallet, &user_spl, true, 0, 4, 17);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Ok(ApricotInstruction::WithdrawAndRemoveUser {
+                user_wallet,
+                user_spl,
+                users_page: consts::get_users_page_k(17),
+                amount: 0,
+                pool_id: 4
+            })
+        );
+
+        let swap_accounts = PoolRegistry::mainnet()
+            .swap_accounts(config::sol_usdc_orca::POOL_ID, 4, 3)
+            .unwrap();
+        let ix = margin_swap(&user_wallet, false, 4, 500, 3, 490, &swap_accounts);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Ok(ApricotInstruction::MarginSwap {
+                user_wallet,
+                target_swap: SwapTarget::Orca,
+                is_buy: false,
+                sell_pool_id: 4,
+                sell_amount: 500,
+                buy_pool_id: 3,
+                buy_amount: 490
+            })
+        );
+
+        let ix = lp_op_endcheck(&[]);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Ok(ApricotInstruction::LpOpEndcheck)
+        );
+
+        let ix = claim_apt_lm_reward(&user_wallet, &user_spl);
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Ok(ApricotInstruction::ClaimAptLmReward {
+                user_wallet,
+                user_apt_spl: user_spl
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let user_wallet = Pubkey::new_unique();
+        let mut ix = borrow(&user_wallet, &Pubkey::new_unique(), 10, 0);
+
+        ix.data.pop();
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(InstructionDecodeError::WrongDataSize {
+                cmd: consts::CMD_BORROW,
+                expected: 1 + 9,
+                actual: 9
+            })
+        );
+        assert_eq!(
+            decode_instruction(&[0x7f], &[]),
+            Err(InstructionDecodeError::UnknownCommand(0x7f))
+        );
+        assert_eq!(
+            decode_instruction(&[], &[]),
+            Err(InstructionDecodeError::EmptyData)
+        );
+        assert_eq!(
+            decode_instruction(&[consts::CMD_REFRESH_USER], &[user_wallet]),
+            Err(InstructionDecodeError::MissingAccounts {
+                cmd: consts::CMD_REFRESH_USER,
+                expected: 3,
+                actual: 1
+            })
+        );
+
+        let mut ix = lp_create(&user_wallet, config::ust_usdc_saber::POOL_ID, 1, 1, 0).unwrap();
+        ix.data[28] = consts::SWAP_FAKE;
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(InstructionDecodeError::InvalidSwapTarget(consts::SWAP_FAKE))
+        );
+        ix.program_id = user_wallet;
+        assert_eq!(
+            ApricotInstruction::from_instruction(&ix),
+            Err(InstructionDecodeError::WrongProgram(user_wallet))
+        );
+    }
+}
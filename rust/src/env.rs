@@ -0,0 +1,595 @@
+// builders mirror the instructions ones, one argument per account or param
+#![allow(clippy::too_many_arguments)]
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+use crate::config::{ApricotConfig, DeploymentKeys, PoolRegistry};
+use crate::decoder::{ApricotInstruction, InstructionDecodeError};
+use crate::instructions::*;
+use crate::lp::{self, LpAction, LpOpError};
+use crate::state::UserInfo;
+use crate::swap::SwapAccounts;
+use crate::utils::{PageStrategy, UserPagesStats};
+
+// The cluster a deployment lives on and the addresses of that deployment. Every derivation in
+// consts and every convenience builder in instructions has a counterpart here that uses the
+// env's addresses instead of the mainnet ones, so the same code runs against a local validator.
+// Both sides call the same _in builders, with DeploymentKeys::MAINNET or the env's keys:
+//
+//   let env = ApricotEnv::deployment(Cluster::Localnet, &program_id, &admin, &vault, pools);
+//   let ix = env.deposit(&wallet, &wallet_spl, amount, pool_id);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Cluster {
+    Mainnet,
+    Devnet,
+    Localnet,
+    Custom(String),
+}
+
+impl Cluster {
+    pub fn rpc_url(&self) -> &str {
+        match self {
+            Cluster::Mainnet => "https://api.mainnet-beta.solana.com",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+            Cluster::Localnet => "http://127.0.0.1:8899",
+            Cluster::Custom(url) => url,
+        }
+    }
+
+    // PubSub endpoint, for Custom the RPC URL with its scheme swapped to ws/wss
+    pub fn ws_url(&self) -> String {
+        match self {
+            Cluster::Mainnet => "wss://api.mainnet-beta.solana.com".to_string(),
+            Cluster::Devnet => "wss://api.devnet.solana.com".to_string(),
+            Cluster::Localnet => "ws://127.0.0.1:8900".to_string(),
+            Cluster::Custom(url) => match url.strip_prefix("http") {
+                Some(rest) => format!("ws{}", rest),
+                None => url.clone(),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ApricotEnv {
+    pub cluster: Cluster,
+    pub config: ApricotConfig,
+}
+
+impl ApricotEnv {
+    pub fn new(cluster: Cluster, config: ApricotConfig) -> Self {
+        ApricotEnv { cluster, config }
+    }
+
+    pub fn mainnet() -> Self {
+        ApricotEnv::new(Cluster::Mainnet, ApricotConfig::mainnet())
+    }
+
+    // see ApricotConfig::derive
+    pub fn deployment(
+        cluster: Cluster,
+        program_id: &Pubkey,
+        admin: &Pubkey,
+        lm_apt_vault: &Pubkey,
+        pools: PoolRegistry,
+    ) -> Self {
+        ApricotEnv::new(
+            cluster,
+            ApricotConfig::derive(program_id, admin, lm_apt_vault, pools),
+        )
+    }
+
+    pub fn keys(&self) -> DeploymentKeys {
+        self.config.keys()
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.config.program_id
+    }
+
+    pub fn base_pda(&self) -> Pubkey {
+        self.config.base_pda
+    }
+
+    pub fn pool_summaries_k(&self) -> Pubkey {
+        self.config.pool_summaries
+    }
+
+    pub fn price_summaries_k(&self) -> Pubkey {
+        self.config.price_summaries
+    }
+
+    pub fn user_pages_stats_k(&self) -> Pubkey {
+        self.config.user_pages_stats
+    }
+
+    pub fn pool_list_k(&self) -> Pubkey {
+        self.keys().pool_list_k()
+    }
+
+    pub fn users_page_k(&self, page_id: u16) -> Pubkey {
+        self.keys().users_page_k(page_id)
+    }
+
+    pub fn asset_pool_k(&self, pool_id: u8) -> Pubkey {
+        self.keys().asset_pool_k(pool_id)
+    }
+
+    pub fn asset_pool_spl_k(&self, token_program_id: &Pubkey, pool_id: u8) -> Pubkey {
+        self.keys().asset_pool_spl_k(token_program_id, pool_id)
+    }
+
+    pub fn asset_price_k(&self, pool_id: u8) -> Pubkey {
+        self.keys().asset_price_k(pool_id)
+    }
+
+    pub fn user_info_k(&self, user_wallet_key: &Pubkey) -> Pubkey {
+        self.keys().user_info_k(user_wallet_key)
+    }
+
+    pub fn asset_pool_stake_table_k(&self, pool_id: u8) -> Pubkey {
+        self.keys().asset_pool_stake_table_k(pool_id)
+    }
+
+    pub fn deposit(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        amount: u64,
+        pool_id: u8,
+    ) -> Instruction {
+        deposit_in(&self.keys(), user_wallet, user_spl, amount, pool_id)
+    }
+
+    pub fn add_user_and_deposit(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        amount: u64,
+        pool_id: u8,
+        page_id: u16,
+    ) -> Instruction {
+        add_user_and_deposit_in(
+            &self.keys(),
+            user_wallet,
+            user_spl,
+            amount,
+            pool_id,
+            page_id,
+        )
+    }
+
+    // deposit for an active user, add_user_and_deposit on a page picked by page_strategy for a
+    // new or inactive one. user_info is None when the wallet has no UserInfo account. None when
+    // the user has to register and every users page is full.
+    pub fn smart_deposit(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        amount: u64,
+        pool_id: u8,
+        user_info: Option<&UserInfo>,
+        user_pages_stats: &UserPagesStats,
+        page_strategy: PageStrategy,
+    ) -> Option<Instruction> {
+        smart_deposit_in(
+            &self.keys(),
+            user_wallet,
+            user_spl,
+            amount,
+            pool_id,
+            user_info,
+            user_pages_stats,
+            page_strategy,
+        )
+    }
+
+    pub fn withdraw(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        withdraw_all: bool,
+        amount: u64,
+        pool_id: u8,
+        page_id: u16,
+    ) -> Instruction {
+        withdraw_in(
+            &self.keys(),
+            user_wallet,
+            user_spl,
+            withdraw_all,
+            amount,
+            pool_id,
+            page_id,
+        )
+    }
+
+    pub fn borrow(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        amount: u64,
+        pool_id: u8,
+    ) -> Instruction {
+        borrow_in(&self.keys(), user_wallet, user_spl, amount, pool_id)
+    }
+
+    pub fn repay(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        user_spl: &Pubkey,    // user's SPL token account
+        repay_all: bool,
+        amount: u64,
+        pool_id: u8,
+    ) -> Instruction {
+        repay_in(
+            &self.keys(),
+            user_wallet,
+            user_spl,
+            repay_all,
+            amount,
+            pool_id,
+        )
+    }
+
+    pub fn refresh_user(&self, user_wallet: &Pubkey) -> Instruction {
+        refresh_user_in(&self.keys(), user_wallet)
+    }
+
+    pub fn extern_liquidate(
+        &self,
+        liquidated_wallet: &Pubkey, // wallet key for account to be liquidated
+        liquidator_wallet: &Pubkey, // wallet key for liquidator, signer
+        liquidator_collateral_spl: &Pubkey, // liquidator's SPL token account for collateral asset
+        liquidator_borrowed_spl: &Pubkey, // liquidator's SPL token account for repaid asset
+        repaid_borrow_amount: u64,
+        min_collateral_amount: u64,
+        borrowed_pool_id: u8,
+        collateral_pool_id: u8,
+    ) -> Instruction {
+        extern_liquidate_in(
+            &self.keys(),
+            liquidated_wallet,
+            liquidator_wallet,
+            liquidator_collateral_spl,
+            liquidator_borrowed_spl,
+            repaid_borrow_amount,
+            min_collateral_amount,
+            borrowed_pool_id,
+            collateral_pool_id,
+        )
+    }
+
+    pub fn update_user_asset_config(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        use_as_collateral: bool,
+        pool_id: u8,
+    ) -> Instruction {
+        update_user_asset_config_in(&self.keys(), user_wallet, use_as_collateral, pool_id)
+    }
+
+    pub fn update_user_config(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        self_liquidation_threshold: u8,
+        post_self_liquidation_ratio_target: u8,
+        post_extern_liquidation_ratio_target: u8,
+    ) -> Instruction {
+        update_user_config_in(
+            &self.keys(),
+            user_wallet,
+            self_liquidation_threshold,
+            post_self_liquidation_ratio_target,
+            post_extern_liquidation_ratio_target,
+        )
+    }
+
+    pub fn margin_swap(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        is_buy: bool,
+        sell_pool_id: u8,
+        sell_amount: u64,
+        buy_pool_id: u8,
+        buy_amount: u64,
+        swap_accounts: &SwapAccounts, // accounts of the DEX the swap goes through
+    ) -> Instruction {
+        margin_swap_in(
+            &self.keys(),
+            user_wallet,
+            is_buy,
+            sell_pool_id,
+            sell_amount,
+            buy_pool_id,
+            buy_amount,
+            swap_accounts,
+        )
+    }
+
+    pub fn lp_create(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        lp_pool_id: u8,
+        left_amount: u64,
+        right_amount: u64,
+        min_lp_amount: u64,
+    ) -> Option<Instruction> {
+        lp_create_in(
+            &self.keys(),
+            &self.config.pools,
+            user_wallet,
+            lp_pool_id,
+            left_amount,
+            right_amount,
+            min_lp_amount,
+        )
+    }
+
+    pub fn lp_redeem(
+        &self,
+        user_wallet: &Pubkey, // user wallet account, needs to be signer
+        lp_pool_id: u8,
+        lp_amount: u64,
+        min_left_amount: u64,
+        min_right_amount: u64,
+    ) -> Option<Instruction> {
+        lp_redeem_in(
+            &self.keys(),
+            &self.config.pools,
+            user_wallet,
+            lp_pool_id,
+            lp_amount,
+            min_left_amount,
+            min_right_amount,
+        )
+    }
+
+    pub fn lp_op_check(&self, check_accounts: &[AccountMeta]) -> Instruction {
+        lp_op_check_full(&self.program_id(), check_accounts)
+    }
+
+    pub fn lp_op_endcheck(&self, check_accounts: &[AccountMeta]) -> Instruction {
+        lp_op_endcheck_full(&self.program_id(), check_accounts)
+    }
+
+    // see lp::build_lp_ops
+    pub fn build_lp_ops(
+        &self,
+        user_wallet: &Pubkey,
+        check_accounts: &[AccountMeta],
+        actions: &[LpAction],
+    ) -> Result<Vec<Instruction>, LpOpError> {
+        lp::build_lp_ops_in(
+            &self.keys(),
+            &self.config.pools,
+            user_wallet,
+            check_accounts,
+            actions,
+        )
+    }
+
+    pub fn make_lm_reward_claimable(&self, user_wallet: &Pubkey) -> Instruction {
+        make_lm_reward_claimable_in(&self.keys(), user_wallet)
+    }
+
+    pub fn claim_apt_lm_reward(&self, user_wallet: &Pubkey, user_apt_spl: &Pubkey) -> Instruction {
+        claim_apt_lm_reward_in(&self.keys(), user_wallet, user_apt_spl)
+    }
+
+    // WrongProgram unless ix was sent to this deployment
+    pub fn decode_instruction(
+        &self,
+        ix: &Instruction,
+    ) -> Result<ApricotInstruction, InstructionDecodeError> {
+        ApricotInstruction::from_instruction_in(&self.keys(), ix)
+    }
+}
+
+impl Default for ApricotEnv {
+    fn default() -> Self {
+        ApricotEnv::mainnet()
+    }
+}
+
+#[cfg(test)]
+pub mod env_test {
+    use super::*;
+    use crate::config;
+    use crate::consts;
+
+    #[test]
+    fn test_mainnet_env_matches_consts() {
+        let env = ApricotEnv::mainnet();
+        assert_eq!(env.keys(), DeploymentKeys::MAINNET);
+        let wallet = Pubkey::new_unique();
+        assert_eq!(env.user_info_k(&wallet), consts::get_user_info_k(&wallet));
+        assert_eq!(env.user_pages_stats_k(), consts::get_user_pages_stats_k());
+        assert_eq!(env.users_page_k(42), consts::get_users_page_k(42));
+        assert_eq!(env.pool_list_k(), consts::get_pool_list_k());
+        for pool_id in 0..=255u8 {
+            assert_eq!(env.asset_pool_k(pool_id), consts::get_asset_pool_k(pool_id));
+            assert_eq!(
+                env.asset_pool_spl_k(&spl_token::ID, pool_id),
+                consts::get_asset_pool_spl_k(&spl_token::ID, pool_id)
+            );
+            assert_eq!(
+                env.asset_price_k(pool_id),
+                consts::get_asset_price_k(pool_id)
+            );
+            assert_eq!(
+                env.asset_pool_stake_table_k(pool_id),
+                consts::get_asset_pool_stake_table_k(pool_id)
+            );
+        }
+    }
+
+    #[test]
+    fn test_mainnet_env_matches_free_builders() {
+        let env = ApricotEnv::mainnet();
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+        assert_eq!(
+            env.deposit(&wallet, &spl, 100, 3),
+            deposit(&wallet, &spl, 100, 3)
+        );
+        assert_eq!(
+            env.add_user_and_deposit(&wallet, &spl, 100, 3, 7),
+            add_user_and_deposit(&wallet, &spl, 100, 3, 7)
+        );
+        let mut stats_data = vec![0u8; std::mem::size_of::<UserPagesStats>()];
+        stats_data[2 * 8] = 2;
+        let stats = UserPagesStats::try_from_bytes(&stats_data).unwrap();
+        assert_eq!(
+            env.smart_deposit(&wallet, &spl, 100, 3, None, stats, PageStrategy::MostFree),
+            smart_deposit(&wallet, &spl, 100, 3, None, stats, PageStrategy::MostFree)
+        );
+        for withdraw_all in [false, true].iter().copied() {
+            assert_eq!(
+                env.withdraw(&wallet, &spl, withdraw_all, 100, 3, 7),
+                withdraw(&wallet, &spl, withdraw_all, 100, 3, 7)
+            );
+        }
+        assert_eq!(
+            env.borrow(&wallet, &spl, 100, 3),
+            borrow(&wallet, &spl, 100, 3)
+        );
+        assert_eq!(
+            env.repay(&wallet, &spl, true, 100, 3),
+            repay(&wallet, &spl, true, 100, 3)
+        );
+        assert_eq!(env.refresh_user(&wallet), refresh_user(&wallet));
+        assert_eq!(
+            env.extern_liquidate(&wallet, &spl, &spl, &spl, 10, 1, 3, 4),
+            extern_liquidate(&wallet, &spl, &spl, &spl, 10, 1, 3, 4)
+        );
+        assert_eq!(
+            env.update_user_asset_config(&wallet, true, 3),
+            update_user_asset_config(&wallet, true, 3)
+        );
+        assert_eq!(
+            env.update_user_config(&wallet, 80, 70, 60),
+            update_user_config(&wallet, 80, 70, 60)
+        );
+        let swap_accounts = env
+            .config
+            .pools
+            .swap_accounts(config::sol_usdc_orca::POOL_ID, 4, 3)
+            .unwrap();
+        assert_eq!(
+            env.margin_swap(&wallet, false, 4, 500, 3, 490, &swap_accounts),
+            margin_swap(&wallet, false, 4, 500, 3, 490, &swap_accounts)
+        );
+        let lp_pool_id = config::sol_usdc_orca::POOL_ID;
+        assert_eq!(
+            env.lp_create(&wallet, lp_pool_id, 1, 2, 0),
+            lp_create(&wallet, lp_pool_id, 1, 2, 0)
+        );
+        assert_eq!(
+            env.lp_redeem(&wallet, lp_pool_id, 3, 1, 1),
+            lp_redeem(&wallet, lp_pool_id, 3, 1, 1)
+        );
+        assert_eq!(
+            env.make_lm_reward_claimable(&wallet),
+            make_lm_reward_claimable(&wallet)
+        );
+        assert_eq!(
+            env.claim_apt_lm_reward(&wallet, &spl),
+            claim_apt_lm_reward(&wallet, &spl)
+        );
+
+        let check_accounts = [AccountMeta::new_readonly(wallet, true)];
+        assert_eq!(
+            env.lp_op_check(&check_accounts),
+            lp_op_check(&check_accounts)
+        );
+        assert_eq!(
+            env.lp_op_endcheck(&check_accounts),
+            lp_op_endcheck(&check_accounts)
+        );
+        let actions = [
+            LpAction::Create {
+                lp_pool_id: config::sol_usdc_orca::POOL_ID,
+                left_amount: 1,
+                right_amount: 2,
+                min_lp_amount: 0,
+            },
+            LpAction::Redeem {
+                lp_pool_id: config::msol_sol_orca::POOL_ID,
+                lp_amount: 10,
+                min_left_amount: 0,
+                min_right_amount: 0,
+            },
+        ];
+        assert_eq!(
+            env.build_lp_ops(&wallet, &check_accounts, &actions),
+            lp::build_lp_ops(&wallet, &check_accounts, &actions)
+        );
+    }
+
+    #[test]
+    fn test_localnet_builders() {
+        let program_id = Pubkey::new_unique();
+        let admin = Pubkey::new_unique();
+        let vault = Pubkey::new_unique();
+        let env = ApricotEnv::deployment(
+            Cluster::Localnet,
+            &program_id,
+            &admin,
+            &vault,
+            PoolRegistry::mainnet(),
+        );
+        assert_eq!(env.cluster.rpc_url(), "http://127.0.0.1:8899");
+
+        let wallet = Pubkey::new_unique();
+        let spl = Pubkey::new_unique();
+        let ix = env.deposit(&wallet, &spl, 100, 3);
+        let mainnet_ix = deposit(&wallet, &spl, 100, 3);
+        assert_eq!(ix.program_id, program_id);
+        assert_eq!(ix.data, mainnet_ix.data);
+        assert_eq!(ix.accounts[2].pubkey, env.user_info_k(&wallet));
+        assert_ne!(ix.accounts[2].pubkey, mainnet_ix.accounts[2].pubkey);
+        assert_eq!(ix.accounts[5].pubkey, env.config.pool_summaries);
+        assert_eq!(
+            env.decode_instruction(&ix),
+            Ok(ApricotInstruction::Deposit {
+                user_wallet: wallet,
+                user_spl: spl,
+                amount: 100,
+                pool_id: 3
+            })
+        );
+        assert_eq!(
+            env.decode_instruction(&mainnet_ix),
+            Err(InstructionDecodeError::WrongProgram(consts::program::ID))
+        );
+
+        let ix = env.withdraw(&wallet, &spl, true, 100, 3, 7);
+        assert_eq!(ix.accounts[2].pubkey, env.config.user_pages_stats);
+        assert_eq!(ix.accounts[3].pubkey, env.users_page_k(7));
+
+        let ix = env.claim_apt_lm_reward(&wallet, &spl);
+        assert_eq!(ix.accounts[0].pubkey, env.config.base_pda);
+        assert_eq!(ix.accounts[4].pubkey, vault);
+
+        let lp_pool_id = config::msol_sol_orca::POOL_ID;
+        let ixs = env
+            .build_lp_ops(
+                &wallet,
+                &[],
+                &[LpAction::Redeem {
+                    lp_pool_id,
+                    lp_amount: 10,
+                    min_left_amount: 0,
+                    min_right_amount: 0,
+                }],
+            )
+            .unwrap();
+        assert!(ixs.iter().all(|ix| ix.program_id == program_id));
+        assert_eq!(ixs[1].accounts[7].pubkey, env.asset_pool_k(lp_pool_id));
+    }
+}
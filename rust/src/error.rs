@@ -0,0 +1,312 @@
+use solana_program::{instruction::InstructionError, program_error::ProgramError, pubkey::Pubkey};
+#[cfg(feature = "client")]
+use solana_sdk::transaction::TransactionError;
+use std::convert::TryFrom;
+use std::fmt;
+
+use crate::consts;
+
+// ranges of consts::ERR_*, the highest nibble of the code
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ApricotErrorCategory {
+    // wrong account supplied, 0x1xxx
+    Account,
+    // malformed instruction data, 0x2xxx
+    Data,
+    // client did something the contract does not expect, 0x3xxx
+    Internal,
+    // user action rejected by protocol rules, 0x4xxx
+    UserLogic,
+}
+
+// declares ApricotError with one variant per consts::ERR_* and the name of each
+macro_rules! apricot_errors {
+    ($($variant:ident => $code:ident,)*) => {
+        #[repr(u32)]
+        #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+        pub enum ApricotError {
+            $($variant = consts::$code,)*
+        }
+
+        impl ApricotError {
+            // name of the consts::ERR_* this error comes from
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(ApricotError::$variant => stringify!($code),)*
+                }
+            }
+        }
+
+        impl TryFrom<u32> for ApricotError {
+            type Error = u32;
+
+            fn try_from(code: u32) -> Result<Self, u32> {
+                match code {
+                    $(consts::$code => Ok(ApricotError::$variant),)*
+                    _ => Err(code),
+                }
+            }
+        }
+    };
+}
+
+apricot_errors! {
+    IncorrectBasePda => ERR_INCORRECT_BASE_PDA,
+    IncorrectUserPagesStats => ERR_INCORRECT_USER_PAGES_STATS,
+    IncorrectUsersPage => ERR_INCORRECT_USERS_PAGE,
+    IncorrectUserInfo => ERR_INCORRECT_USER_INFO,
+    IncorrectAssetPool => ERR_INCORRECT_ASSET_POOL,
+    IncorrectAssetPrice => ERR_INCORRECT_ASSET_PRICE,
+    IncorrectAssetPoolSpl => ERR_INCORRECT_ASSET_POOL_SPL,
+    IncorrectUserAssetInfo => ERR_INCORRECT_USER_ASSET_INFO,
+    MissingActiveAccounts => ERR_MISSING_ACTIVE_ACCOUNTS,
+    IncorrectIntermediateSpl => ERR_INCORRECT_INTERMEDIATE_SPL,
+    IncorrectSellMarket => ERR_INCORRECT_SELL_MARKET,
+    IncorrectBuyMarket => ERR_INCORRECT_BUY_MARKET,
+    IncorrectSerumProgram => ERR_INCORRECT_SERUM_PROGRAM,
+    IncorrectAdmin => ERR_INCORRECT_ADMIN,
+    IncorrectIntermediateSplOwner => ERR_INCORRECT_INTERMEDIATE_SPL_OWNER,
+    IncorrectPoolList => ERR_INCORRECT_POOL_LIST,
+    IncorrectPoolSummaries => ERR_INCORRECT_POOL_SUMMARIES,
+    IncorrectPriceSummaries => ERR_INCORRECT_PRICE_SUMMARIES,
+    IncorrectPricePda => ERR_INCORRECT_PRICE_PDA,
+    IncorrectTokenProgram => ERR_INCORRECT_TOKEN_PROGRAM,
+    IncorrectAssetPoolAtokenMint => ERR_INCORRECT_ASSET_POOL_ATOKEN_MINT,
+    IncorrectInstructionsSysvar => ERR_INCORRECT_INSTRUCTIONS_SYSVAR,
+    MissingPageId => ERR_MISSING_PAGE_ID,
+    PageIdTooLarge => ERR_PAGE_ID_TOO_LARGE,
+    MissingAmount => ERR_MISSING_AMOUNT,
+    MissingMintSeedStr => ERR_MISSING_MINT_SEED_STR,
+    MissingActiveMintSeedStr => ERR_MISSING_ACTIVE_MINT_SEED_STR,
+    WrongDataSize => ERR_WRONG_DATA_SIZE,
+    AccountAlreadyAdded => ERR_ACCOUNT_ALREADY_ADDED,
+    NoAvailableSlots => ERR_NO_AVAILABLE_SLOTS,
+    AccountNotAdded => ERR_ACCOUNT_NOT_ADDED,
+    WalletDidNotSign => ERR_WALLET_DID_NOT_SIGN,
+    MaximumNumPoolsReached => ERR_MAXIMUM_NUM_POOLS_REACHED,
+    UserHasNoSuchAsset => ERR_USER_HAS_NO_SUCH_ASSET,
+    NeedAtLeastBuyOrSell => ERR_NEED_AT_LEAST_BUY_OR_SELL,
+    InsufficientFees => ERR_INSUFFICIENT_FEES,
+    DepositLessThanMinimum => ERR_DEPOSIT_LESS_THAN_MINIMUM,
+    InsufficientDeposit => ERR_INSUFFICIENT_DEPOSIT,
+    PoolNoFreeFund => ERR_POOL_NO_FREE_FUND,
+    PleaseWithdrawAll => ERR_PLEASE_WITHDRAW_ALL,
+    InsufficientBorrowPower => ERR_INSUFFICIENT_BORROW_POWER,
+    CannotRepayMoreThanDebt => ERR_CANNOT_REPAY_MORE_THAN_DEBT,
+    WithdrawlBelowMinCollateralRatio => ERR_WITHDRAWL_BELOW_MIN_COLLATERAL_RATIO,
+    LiquidationNotReached => ERR_LIQUIDATION_NOT_REACHED,
+    LiquidatorAskedTooMuchCollateral => ERR_LIQUIDATOR_ASKED_TOO_MUCH_COLLATERAL,
+    AccountNotEnoughDebtForLiquidation => ERR_ACCOUNT_NOT_ENOUGH_DEBT_FOR_LIQUIDATION,
+    AccountNotEnoughCollateralForLiquidation => ERR_ACCOUNT_NOT_ENOUGH_COLLATERAL_FOR_LIQUIDATION,
+    ExceedsLiquidationLimit => ERR_EXCEEDS_LIQUIDATION_LIMIT,
+    SelfDeleverageFactorTooLarge => ERR_SELF_DELEVERAGE_FACTOR_TOO_LARGE,
+    PostDeleverageFactorTooLarge => ERR_POST_DELEVERAGE_FACTOR_TOO_LARGE,
+    Deprecated => ERR_DEPRECATED_XXXXXXXXXXXXXX,
+    SelfDeleverageFactorNotReached => ERR_SELF_DELEVERAGE_FACTOR_NOT_REACHED,
+    SelfDeleverageTargetExceeded => ERR_SELF_DELEVERAGE_TARGET_EXCEEDED,
+    SelfDeleverageHighSlippage => ERR_SELF_DELEVERAGE_HIGH_SLIPPAGE,
+    MaxNumAssetsReached => ERR_MAX_NUM_ASSETS_REACHED,
+    SwapBoughtLessThanMin => ERR_SWAP_BOUGHT_LESS_THAN_MIN,
+    AssetNotUsedAsCollateral => ERR_ASSET_NOT_USED_AS_COLLATERAL,
+    InsufficientWalletBalance => ERR_INSUFFICIENT_WALLET_BALANCE,
+    SwapLpGotLessThanMin => ERR_SWAP_LP_GOT_LESS_THAN_MIN,
+    AssistAlreadyExecuted => ERR_ASSIST_ALREADY_EXECUTED,
+    AssistNotEnabled => ERR_ASSIST_NOT_ENABLED,
+    AssistCheckMustBeFirst => ERR_ASSIST_CHECK_MUST_BE_FIRST,
+    AssistActionNotAllowed => ERR_ASSIST_ACTION_NOT_ALLOWED,
+    AssistInconsistentAction => ERR_ASSIST_INCONSISTENT_ACTION,
+}
+
+impl ApricotError {
+    pub fn code(&self) -> u32 {
+        *self as u32
+    }
+
+    pub fn category(&self) -> ApricotErrorCategory {
+        match self.code() >> 12 {
+            0x1 => ApricotErrorCategory::Account,
+            0x2 => ApricotErrorCategory::Data,
+            0x3 => ApricotErrorCategory::Internal,
+            _ => ApricotErrorCategory::UserLogic,
+        }
+    }
+
+    pub fn from_program_error(err: &ProgramError) -> Option<Self> {
+        match err {
+            ProgramError::Custom(code) => Self::try_from(*code).ok(),
+            _ => None,
+        }
+    }
+
+    // for TransactionError::InstructionError(_, err)
+    pub fn from_instruction_error(err: &InstructionError) -> Option<Self> {
+        match err {
+            InstructionError::Custom(code) => Self::try_from(*code).ok(),
+            _ => None,
+        }
+    }
+
+    // error of a failed transaction, e.g. of a transaction status or an RPC client error
+    #[cfg(feature = "client")]
+    pub fn from_transaction_error(err: &TransactionError) -> Option<Self> {
+        match err {
+            TransactionError::InstructionError(_, err) => Self::from_instruction_error(err),
+            _ => None,
+        }
+    }
+
+    // picks the code out of an error message such as the one of a failed simulation or
+    // a displayed TransactionError, "... custom program error: 0x4004"
+    pub fn from_error_message(msg: &str) -> Option<Self> {
+        const MARKER: &str = "custom program error: 0x";
+        let start = msg.rfind(MARKER)? + MARKER.len();
+        let hex: String = msg[start..]
+            .chars()
+            .take_while(|c| c.is_ascii_hexdigit())
+            .collect();
+        let code = u32::from_str_radix(&hex, 16).ok()?;
+        Self::try_from(code).ok()
+    }
+}
+
+impl fmt::Display for ApricotError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::error::Error for ApricotError {}
+
+impl From<ApricotError> for ProgramError {
+    fn from(err: ApricotError) -> Self {
+        ProgramError::Custom(err.code())
+    }
+}
+
+// failure to read an account or parameter struct out of raw bytes, see utils::try_cast
+#[derive(Clone, Debug, PartialEq)]
+pub enum ApricotDecodeError {
+    TooShort { expected: usize, actual: usize },
+    Misaligned,
+    // account is not owned by the program it is supposed to be created by
+    WrongOwner { expected: Pubkey, actual: Pubkey },
+    // a field holds a value the contract never writes, e.g. num_assets beyond MAX_ASSETS_PER_USER
+    InvalidField { field: &'static str, value: u64 },
+    AccountBorrow(ProgramError),
+}
+
+impl fmt::Display for ApricotDecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ApricotDecodeError::TooShort { expected, actual } => write!(
+                f,
+                "account data too short, expected {} bytes, got {}",
+                expected, actual
+            ),
+            ApricotDecodeError::Misaligned => write!(f, "account data is misaligned"),
+            ApricotDecodeError::WrongOwner { expected, actual } => {
+                write!(f, "account owned by {}, expected {}", actual, expected)
+            }
+            ApricotDecodeError::InvalidField { field, value } => {
+                write!(f, "invalid {}: {}", field, value)
+            }
+            ApricotDecodeError::AccountBorrow(err) => {
+                write!(f, "cannot borrow account data: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ApricotDecodeError {}
+
+impl From<ApricotDecodeError> for ProgramError {
+    fn from(err: ApricotDecodeError) -> Self {
+        match err {
+            ApricotDecodeError::TooShort { .. } => ProgramError::AccountDataTooSmall,
+            ApricotDecodeError::WrongOwner { .. } => ProgramError::IncorrectProgramId,
+            ApricotDecodeError::AccountBorrow(err) => err,
+            _ => ProgramError::InvalidAccountData,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod error_test {
+    use super::*;
+
+    #[test]
+    fn test_apricot_error_from_code() {
+        let err = ApricotError::try_from(0x4004).unwrap();
+        assert_eq!(err, ApricotError::InsufficientBorrowPower);
+        assert_eq!(err.to_string(), "ERR_INSUFFICIENT_BORROW_POWER");
+        assert_eq!(err.category(), ApricotErrorCategory::UserLogic);
+        assert_eq!(
+            ApricotError::IncorrectInstructionsSysvar.category(),
+            ApricotErrorCategory::Account
+        );
+        assert_eq!(
+            ApricotError::WrongDataSize.category(),
+            ApricotErrorCategory::Data
+        );
+        assert_eq!(
+            ApricotError::InsufficientFees.category(),
+            ApricotErrorCategory::Internal
+        );
+        assert_eq!(ApricotError::try_from(0x4022), Err(0x4022));
+        assert_eq!(
+            ApricotError::try_from(consts::ERR_DEPRECATED_XXXXXXXXXXXXXX),
+            Ok(ApricotError::Deprecated)
+        );
+    }
+
+    #[test]
+    fn test_apricot_error_extraction() {
+        let program_error: ProgramError = ApricotError::PoolNoFreeFund.into();
+        assert_eq!(program_error, ProgramError::Custom(0x4002));
+        assert_eq!(
+            ApricotError::from_program_error(&program_error),
+            Some(ApricotError::PoolNoFreeFund)
+        );
+        assert_eq!(
+            ApricotError::from_program_error(&ProgramError::InvalidArgument),
+            None
+        );
+        assert_eq!(
+            ApricotError::from_instruction_error(&InstructionError::Custom(0x100a)),
+            Some(ApricotError::IncorrectSellMarket)
+        );
+        assert_eq!(
+            ApricotError::from_error_message(
+                "Error processing Instruction 1: custom program error: 0x4013"
+            ),
+            Some(ApricotError::SwapBoughtLessThanMin)
+        );
+        assert_eq!(
+            ApricotError::from_error_message("insufficient funds for rent"),
+            None
+        );
+    }
+
+    #[cfg(feature = "client")]
+    #[test]
+    fn test_apricot_error_from_transaction_error() {
+        assert_eq!(
+            ApricotError::from_transaction_error(&TransactionError::InstructionError(
+                1,
+                InstructionError::Custom(0x4013)
+            )),
+            Some(ApricotError::SwapBoughtLessThanMin)
+        );
+        assert_eq!(
+            ApricotError::from_transaction_error(&TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidArgument
+            )),
+            None
+        );
+        assert_eq!(
+            ApricotError::from_transaction_error(&TransactionError::BlockhashNotFound),
+            None
+        );
+    }
+}
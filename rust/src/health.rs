@@ -0,0 +1,195 @@
+use std::fmt;
+
+use crate::view::{AssetPoolView, UserInfoView};
+
+// Borrow power of a user, mirroring ApiBorrowPowerInfo of the TS SDK. Values are in USD.
+//
+// collateral_ratio is total_borrow / max_borrow_allowed, the limits are thresholds for it:
+// borrowing and withdrawing stop at safe_limit, assist is forced at force_assist_limit and the
+// user can be liquidated past liquidation_limit.
+
+// same as the TS SDK, sdk-ts/src/constants/configs.ts
+// assist deleverages users regardless of their own settings once borrows exceed the ltv-weighted
+// collateral
+pub const FORCE_ASSIST_LIMIT: f64 = 1.0;
+// liquidation is allowed from here on, below it the program fails with ERR_LIQUIDATION_NOT_REACHED
+pub const LIQUIDATION_LIMIT: f64 = 1.01;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BorrowPowerInfo {
+    pub total_deposit: f64,
+    pub total_collateral: f64,
+    pub max_borrow_allowed: f64,
+    pub total_borrow: f64,
+    pub collateral_ratio: f64,
+    pub safe_limit: f64,
+    pub force_assist_limit: f64,
+    pub liquidation_limit: f64,
+}
+
+impl BorrowPowerInfo {
+    // borrow value that can still be added before reaching safe_limit
+    pub fn remaining_safe_borrow(&self) -> f64 {
+        (self.max_borrow_allowed * self.safe_limit - self.total_borrow).max(0.0)
+    }
+
+    pub fn is_safe(&self) -> bool {
+        self.collateral_ratio <= self.safe_limit
+    }
+
+    pub fn is_force_assisted(&self) -> bool {
+        self.collateral_ratio > self.force_assist_limit
+    }
+
+    pub fn is_liquidatable(&self) -> bool {
+        self.collateral_ratio > self.liquidation_limit
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum HealthError {
+    MissingPool { pool_id: u8 },
+    MissingPrice { pool_id: u8 },
+}
+
+impl fmt::Display for HealthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            HealthError::MissingPool { pool_id } => write!(f, "pool {} not given", pool_id),
+            HealthError::MissingPrice { pool_id } => write!(f, "no price for pool {}", pool_id),
+        }
+    }
+}
+
+impl std::error::Error for HealthError {}
+
+// Prices are USD per UI unit of each pool's token, as given by the caller's price source.
+pub fn calculate_borrow_power(
+    user: &UserInfoView,
+    pools: &[AssetPoolView],
+    price_in_usd: impl Fn(u8) -> Option<f64>,
+) -> Result<BorrowPowerInfo, HealthError> {
+    let mut total_deposit = 0.0;
+    let mut total_collateral = 0.0;
+    let mut max_borrow_allowed = 0.0;
+    let mut safe_borrow_allowed = 0.0;
+    let mut total_borrow = 0.0;
+
+    for asset in user.assets() {
+        let pool_id = asset.pool_id();
+        let pool = pools
+            .iter()
+            .find(|pool| pool.pool_id() == pool_id)
+            .ok_or(HealthError::MissingPool { pool_id })?;
+        let price = price_in_usd(pool_id).ok_or(HealthError::MissingPrice { pool_id })?;
+
+        let deposit_value = asset.deposit_amount(pool) * price;
+        total_deposit += deposit_value;
+        if asset.use_as_collateral() {
+            total_collateral += deposit_value;
+            max_borrow_allowed += deposit_value * pool.ltv();
+            safe_borrow_allowed += deposit_value * pool.ltv() * pool.safe_factor();
+        }
+        total_borrow += asset.borrow_amount(pool) * price;
+    }
+
+    let collateral_ratio = if total_borrow == 0.0 {
+        0.0
+    } else if max_borrow_allowed == 0.0 {
+        f64::INFINITY
+    } else {
+        total_borrow / max_borrow_allowed
+    };
+    // safe_factor of each pool weighted by its share of the borrow power
+    let safe_limit = if max_borrow_allowed == 0.0 {
+        0.0
+    } else {
+        safe_borrow_allowed / max_borrow_allowed
+    };
+
+    Ok(BorrowPowerInfo {
+        total_deposit,
+        total_collateral,
+        max_borrow_allowed,
+        total_borrow,
+        collateral_ratio,
+        safe_limit,
+        force_assist_limit: FORCE_ASSIST_LIMIT,
+        liquidation_limit: LIQUIDATION_LIMIT,
+    })
+}
+
+#[cfg(test)]
+pub mod health_test {
+    use super::*;
+    use crate::state::{AssetPool, RawAmt, UserInfo};
+    use crate::utils;
+
+    pub fn pool_view(pool_id: u8, ltv: f64, safe_factor: f64) -> AssetPoolView {
+        let mut data = vec![0u8; std::mem::size_of::<AssetPool>()];
+        let pool = utils::try_cast_mut::<AssetPool>(&mut data).unwrap();
+        pool.pool_id = pool_id;
+        pool.ltv = ltv;
+        pool.safe_factor = safe_factor;
+        AssetPoolView::new(AssetPool::from_bytes(&data), 6)
+    }
+
+    #[test]
+    fn test_calculate_borrow_power() {
+        let mut data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user_info = utils::try_cast_mut::<UserInfo>(&mut data).unwrap();
+        user_info.num_assets = 2;
+        user_info.user_asset_info[0].pool_id = 0;
+        user_info.user_asset_info[0].use_as_collateral = 1;
+        user_info.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(2_000_000);
+        user_info.user_asset_info[1].pool_id = 3;
+        user_info.user_asset_info[1].deposit_amount = RawAmt::from_native_amount(10_000_000);
+        user_info.user_asset_info[1].borrow_amount = RawAmt::from_native_amount(30_000_000);
+        let user = UserInfoView::from(UserInfo::from_bytes(&data));
+
+        let pools = [pool_view(0, 0.5, 0.8), pool_view(3, 0.9, 0.9)];
+        let prices = |pool_id| match pool_id {
+            0 => Some(100.0),
+            3 => Some(1.0),
+            _ => None,
+        };
+        let info = calculate_borrow_power(&user, &pools, prices).unwrap();
+        assert_eq!(info.total_deposit, 210.0);
+        assert_eq!(info.total_collateral, 200.0);
+        assert_eq!(info.max_borrow_allowed, 100.0);
+        assert_eq!(info.total_borrow, 30.0);
+        assert_eq!(info.collateral_ratio, 0.3);
+        assert!((info.safe_limit - 0.8).abs() < 1e-12);
+        assert!((info.remaining_safe_borrow() - 50.0).abs() < 1e-9);
+        assert!(info.is_safe() && !info.is_liquidatable());
+
+        assert_eq!(
+            calculate_borrow_power(&user, &pools[..1], prices).err(),
+            Some(HealthError::MissingPool { pool_id: 3 })
+        );
+    }
+
+    #[test]
+    fn test_force_assist_before_liquidation() {
+        let mut data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user_info = utils::try_cast_mut::<UserInfo>(&mut data).unwrap();
+        user_info.num_assets = 2;
+        user_info.user_asset_info[0].pool_id = 0;
+        user_info.user_asset_info[0].use_as_collateral = 1;
+        user_info.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(2_000_000);
+        user_info.user_asset_info[1].pool_id = 3;
+        user_info.user_asset_info[1].borrow_amount = RawAmt::from_native_amount(100_500_000);
+        let user = UserInfoView::from(UserInfo::from_bytes(&data));
+
+        let pools = [pool_view(0, 0.5, 0.8), pool_view(3, 0.9, 0.9)];
+        let prices = |pool_id| match pool_id {
+            0 => Some(100.0),
+            3 => Some(1.0),
+            _ => None,
+        };
+        let info = calculate_borrow_power(&user, &pools, prices).unwrap();
+        assert!((info.collateral_ratio - 1.005).abs() < 1e-9);
+        assert!(info.is_force_assisted());
+        assert!(!info.is_liquidatable());
+    }
+}
@@ -1,52 +1,89 @@
+// _full builders take every account explicitly, one argument each
+// convenience builders go through the _in builders with DeploymentKeys::MAINNET, ApricotEnv calls
+// the same _in builders with the keys of any other deployment
+#![allow(clippy::too_many_arguments)]
+
 use solana_program::{
     instruction::{AccountMeta, Instruction},
     pubkey::Pubkey,
     system_program,
 };
-use spl_token;
 
+use crate::config::{DeploymentKeys, PoolRegistry};
 use crate::consts;
+use crate::state::UserInfo;
+use crate::swap::SwapAccounts;
+use crate::utils::{self, PageStrategy, Pod, UserPagesStats};
 
-#[repr(packed)]
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct UpdateUserAssetConfigParam {
     pub use_as_collateral: u8,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for UpdateUserAssetConfigParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct UpdateUserConfigParam {
+    pub self_liquidation_threshold: u8,
+    pub post_self_liquidation_ratio_target: u8,
+    pub post_extern_liquidation_ratio_target: u8,
+}
+
+unsafe impl Pod for UpdateUserConfigParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct AddUserAndDepositParam {
     pub page_id: u16,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for AddUserAndDepositParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct DepositParam {
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for DepositParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct WithdrawParam {
     pub withdraw_all: u8,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for WithdrawParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct BorrowParam {
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for BorrowParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct RepayParam {
     pub repay_all: u8,
     pub amount: u64,
     pub pool_id: u8,
 }
 
-#[repr(packed)]
+unsafe impl Pod for RepayParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct ExternLiquidateParam {
     // how much collateral liquidator wants to receive
     pub min_collateral_amount: u64,
@@ -56,10 +93,84 @@ pub struct ExternLiquidateParam {
     pub borrowed_pool_id: u8,
 }
 
+unsafe impl Pod for ExternLiquidateParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct MarginSwapParam {
+    pub is_buy: u8,
+    // how much of the sell asset to swap out (exact amount when selling)
+    pub sell_amount: u64,
+    // how much of the buy asset to receive (exact amount when buying, minimum when selling)
+    pub buy_amount: u64,
+    pub sell_pool_id: u8,
+    pub buy_pool_id: u8,
+    pub target_swap: u8,
+}
+
+unsafe impl Pod for MarginSwapParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct LpCreateParam {
+    pub left_amount: u64,
+    pub right_amount: u64,
+    // minimum amount of LP token to receive
+    pub min_lp_amount: u64,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub target_swap: u8,
+}
+
+unsafe impl Pod for LpCreateParam {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct LpRedeemParam {
+    // minimum amounts of left and right asset to receive
+    pub min_left_amount: u64,
+    pub min_right_amount: u64,
+    pub lp_amount: u64,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub lp_pool_id: u8,
+    pub target_swap: u8,
+}
+
+unsafe impl Pod for LpRedeemParam {}
+
+// venue a margin swap is routed through, its accounts (swap::SwapAccounts) are appended after the
+// accounts common to every margin swap
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SwapTarget {
+    Serum = consts::SWAP_SERUM,
+    Raydium = consts::SWAP_RAYDIUM,
+    Saber = consts::SWAP_SABER,
+    Mercurial = consts::SWAP_MERCURIAL,
+    Orca = consts::SWAP_ORCA,
+}
+
+impl SwapTarget {
+    pub fn from_u8(target_swap: u8) -> Option<Self> {
+        match target_swap {
+            consts::SWAP_SERUM => Some(SwapTarget::Serum),
+            consts::SWAP_RAYDIUM => Some(SwapTarget::Raydium),
+            consts::SWAP_SABER => Some(SwapTarget::Saber),
+            consts::SWAP_MERCURIAL => Some(SwapTarget::Mercurial),
+            consts::SWAP_ORCA => Some(SwapTarget::Orca),
+            _ => None,
+        }
+    }
+}
+
 #[inline(always)]
-pub fn mut_cast<T>(data: &mut [u8]) -> &mut T {
-    assert!(data.len() >= std::mem::size_of::<T>());
-    return unsafe { std::mem::transmute(data.as_ptr()) };
+pub fn mut_cast<T: Pod>(data: &mut [u8]) -> &mut T {
+    match utils::try_cast_mut::<T>(data) {
+        Ok(t) => t,
+        Err(err) => panic!("{}", err),
+    }
 }
 
 pub fn deposit(
@@ -67,17 +178,33 @@ pub fn deposit(
     user_spl: &Pubkey,    // user's SPL token account
     amount: u64,
     pool_id: u8,
+) -> Instruction {
+    deposit_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        amount,
+        pool_id,
+    )
+}
+
+pub(crate) fn deposit_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
 ) -> Instruction {
     deposit_full(
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
+        &keys.user_info_k(user_wallet),
+        &keys.asset_pool_k(pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         amount,
         pool_id,
     )
@@ -101,7 +228,7 @@ pub fn deposit_full(
     let mut buffer = vec![0; data_size];
 
     buffer[0] = consts::CMD_DEPOSIT;
-    let mut param = mut_cast::<DepositParam>(&mut buffer[1..]);
+    let param = mut_cast::<DepositParam>(&mut buffer[1..]);
     param.amount = amount;
     param.pool_id = pool_id;
 
@@ -127,20 +254,38 @@ pub fn add_user_and_deposit(
     amount: u64,
     pool_id: u8,
     page_id: u16,
+) -> Instruction {
+    add_user_and_deposit_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        amount,
+        pool_id,
+        page_id,
+    )
+}
+
+pub(crate) fn add_user_and_deposit_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
+    page_id: u16,
 ) -> Instruction {
     add_user_and_deposit_full(
         user_wallet,
         user_spl,
-        &consts::get_user_pages_stats_k(),
-        &consts::get_users_page_k(page_id),
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
+        &keys.user_pages_stats,
+        &keys.users_page_k(page_id),
+        &keys.user_info_k(user_wallet),
+        &keys.asset_pool_k(pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
         &system_program::ID,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         amount,
         pool_id,
         page_id,
@@ -169,7 +314,7 @@ pub fn add_user_and_deposit_full(
     let mut buffer = vec![0; data_size];
 
     buffer[0] = consts::CMD_ADD_USER_AND_DEPOSIT;
-    let mut param = mut_cast::<AddUserAndDepositParam>(&mut buffer[1..]);
+    let param = mut_cast::<AddUserAndDepositParam>(&mut buffer[1..]);
     param.amount = amount;
     param.pool_id = pool_id;
     param.page_id = page_id;
@@ -193,6 +338,52 @@ pub fn add_user_and_deposit_full(
     }
 }
 
+// picks deposit or add_user_and_deposit from the fetched accounts, see ApricotEnv::smart_deposit
+pub fn smart_deposit(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
+    user_info: Option<&UserInfo>, // None when get_user_info_k(user_wallet) doesn't exist
+    user_pages_stats: &UserPagesStats, // account at consts::get_user_pages_stats_k()
+    page_strategy: PageStrategy,
+) -> Option<Instruction> {
+    smart_deposit_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        amount,
+        pool_id,
+        user_info,
+        user_pages_stats,
+        page_strategy,
+    )
+}
+
+pub(crate) fn smart_deposit_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey,
+    user_spl: &Pubkey,
+    amount: u64,
+    pool_id: u8,
+    user_info: Option<&UserInfo>,
+    user_pages_stats: &UserPagesStats,
+    page_strategy: PageStrategy,
+) -> Option<Instruction> {
+    if matches!(user_info.map(UserInfo::is_active), Some(true)) {
+        return Some(deposit_in(keys, user_wallet, user_spl, amount, pool_id));
+    }
+    let page_id = user_pages_stats.select_page(page_strategy)?;
+    Some(add_user_and_deposit_in(
+        keys,
+        user_wallet,
+        user_spl,
+        amount,
+        pool_id,
+        page_id,
+    ))
+}
+
 pub fn withdraw(
     user_wallet: &Pubkey, // user wallet account, needs to be signer
     user_spl: &Pubkey,    // user's SPL token account
@@ -200,41 +391,63 @@ pub fn withdraw(
     amount: u64,
     pool_id: u8,
     page_id: u16,
+) -> Instruction {
+    withdraw_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        withdraw_all,
+        amount,
+        pool_id,
+        page_id,
+    )
+}
+
+pub(crate) fn withdraw_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    withdraw_all: bool,
+    amount: u64,
+    pool_id: u8,
+    page_id: u16,
 ) -> Instruction {
     withdraw_full(
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &consts::get_base_pda(),
+        &keys.user_pages_stats,
+        &keys.users_page_k(page_id),
+        &keys.user_info_k(user_wallet),
+        &keys.asset_pool_k(pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &keys.base_pda,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         withdraw_all,
         amount,
         pool_id,
-        page_id,
     )
 }
 
 pub fn withdraw_full(
-    user_wallet: &Pubkey,     // user wallet account, needs to be signer
-    user_spl: &Pubkey,        // user's SPL token account
-    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
-    asset_pool: &Pubkey,      // consts::get_asset_pool_k(pool_id)
-    asset_pool_spl: &Pubkey,  // consts::get_asset_pool_spl_k(token_program, pool_id)
-    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
-    price_summaries: &Pubkey, // consts::get_price_summaries_k()
-    base_pda: &Pubkey,        // consts::get_base_pda()
+    user_wallet: &Pubkey,      // user wallet account, needs to be signer
+    user_spl: &Pubkey,         // user's SPL token account
+    user_pages_stats: &Pubkey, // consts::get_user_pages_stats_k(), only used with withdraw_all
+    users_page: &Pubkey,       // consts::get_users_page_k(page_id), only used with withdraw_all
+    user_info: &Pubkey,        // consts::get_user_info_k(user_wallet_key)
+    asset_pool: &Pubkey,       // consts::get_asset_pool_k(pool_id)
+    asset_pool_spl: &Pubkey,   // consts::get_asset_pool_spl_k(token_program, pool_id)
+    pool_summaries: &Pubkey,   // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey,  // consts::get_price_summaries_k()
+    base_pda: &Pubkey,         // consts::get_base_pda()
     token_program: &Pubkey,
     program_id: &Pubkey, // consts::program::ID
 
     withdraw_all: bool,
     amount: u64,
     pool_id: u8,
-    page_id: u16,
 ) -> Instruction {
     let data_size = 1 + std::mem::size_of::<WithdrawParam>();
     let mut buffer = vec![0; data_size];
@@ -244,7 +457,7 @@ pub fn withdraw_full(
     } else {
         consts::CMD_WITHDRAW
     };
-    let mut param = mut_cast::<WithdrawParam>(&mut buffer[1..]);
+    let param = mut_cast::<WithdrawParam>(&mut buffer[1..]);
     param.withdraw_all = if withdraw_all { 1 } else { 0 };
     param.amount = amount;
     param.pool_id = pool_id;
@@ -254,8 +467,8 @@ pub fn withdraw_full(
         AccountMeta::new(*user_spl, false),
     ];
     if withdraw_all {
-        accounts.push(AccountMeta::new(consts::get_user_pages_stats_k(), false));
-        accounts.push(AccountMeta::new(consts::get_users_page_k(page_id), false));
+        accounts.push(AccountMeta::new(*user_pages_stats, false));
+        accounts.push(AccountMeta::new(*users_page, false));
     }
     accounts.push(AccountMeta::new(*user_info, false));
     accounts.push(AccountMeta::new(*asset_pool, false));
@@ -266,7 +479,7 @@ pub fn withdraw_full(
     accounts.push(AccountMeta::new_readonly(*token_program, false));
     Instruction {
         program_id: *program_id,
-        accounts: accounts,
+        accounts,
         data: buffer,
     }
 }
@@ -276,18 +489,34 @@ pub fn borrow(
     user_spl: &Pubkey,    // user's SPL token account
     amount: u64,
     pool_id: u8,
+) -> Instruction {
+    borrow_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        amount,
+        pool_id,
+    )
+}
+
+pub(crate) fn borrow_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    amount: u64,
+    pool_id: u8,
 ) -> Instruction {
     borrow_full(
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
-        &consts::get_price_summaries_k(),
-        &consts::get_base_pda(),
+        &keys.user_info_k(user_wallet),
+        &keys.asset_pool_k(pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &keys.base_pda,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         amount,
         pool_id,
     )
@@ -312,7 +541,7 @@ pub fn borrow_full(
     let mut buffer = vec![0; data_size];
 
     buffer[0] = consts::CMD_BORROW;
-    let mut param = mut_cast::<BorrowParam>(&mut buffer[1..]);
+    let param = mut_cast::<BorrowParam>(&mut buffer[1..]);
     param.amount = amount;
     param.pool_id = pool_id;
 
@@ -339,16 +568,34 @@ pub fn repay(
     repay_all: bool,
     amount: u64,
     pool_id: u8,
+) -> Instruction {
+    repay_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        user_spl,
+        repay_all,
+        amount,
+        pool_id,
+    )
+}
+
+pub(crate) fn repay_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_spl: &Pubkey,    // user's SPL token account
+    repay_all: bool,
+    amount: u64,
+    pool_id: u8,
 ) -> Instruction {
     repay_full(
         user_wallet,
         user_spl,
-        &consts::get_user_info_k(user_wallet),
-        &consts::get_asset_pool_k(pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, pool_id),
-        &consts::get_pool_summaries_k(),
+        &keys.user_info_k(user_wallet),
+        &keys.asset_pool_k(pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, pool_id),
+        &keys.pool_summaries,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         repay_all,
         amount,
         pool_id,
@@ -373,7 +620,7 @@ pub fn repay_full(
     let mut buffer = vec![0; data_size];
 
     buffer[0] = consts::CMD_REPAY;
-    let mut param = mut_cast::<RepayParam>(&mut buffer[1..]);
+    let param = mut_cast::<RepayParam>(&mut buffer[1..]);
     param.repay_all = if repay_all { 1 } else { 0 };
     param.amount = amount;
     param.pool_id = pool_id;
@@ -395,21 +642,38 @@ pub fn repay_full(
 
 pub fn refresh_user(user_wallet: &Pubkey, // user wallet account
 ) -> Instruction {
-    let data_size = 1 + 0; // no param
-    let mut buffer = vec![0; data_size];
+    refresh_user_in(&DeploymentKeys::MAINNET, user_wallet)
+}
 
-    let program_id = consts::program::ID;
-    let user_info = consts::get_user_info_k(user_wallet);
-    let pool_summaries = consts::get_pool_summaries_k();
+pub(crate) fn refresh_user_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account
+) -> Instruction {
+    refresh_user_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.pool_summaries,
+        &keys.program_id,
+    )
+}
+
+pub fn refresh_user_full(
+    user_wallet: &Pubkey,    // user wallet account
+    user_info: &Pubkey,      // consts::get_user_info_k(user_wallet_key)
+    pool_summaries: &Pubkey, // consts::get_pool_summaries_k()
+    program_id: &Pubkey,     // consts::program::ID
+) -> Instruction {
+    let data_size = 1; // no param
+    let mut buffer = vec![0; data_size];
 
     buffer[0] = consts::CMD_REFRESH_USER;
 
     Instruction {
-        program_id: program_id,
+        program_id: *program_id,
         accounts: vec![
             AccountMeta::new_readonly(*user_wallet, false),
-            AccountMeta::new(user_info, false),
-            AccountMeta::new_readonly(pool_summaries, false),
+            AccountMeta::new(*user_info, false),
+            AccountMeta::new_readonly(*pool_summaries, false),
         ],
         data: buffer,
     }
@@ -424,21 +688,45 @@ pub fn extern_liquidate(
     min_collateral_amount: u64,
     borrowed_pool_id: u8,
     collateral_pool_id: u8,
+) -> Instruction {
+    extern_liquidate_in(
+        &DeploymentKeys::MAINNET,
+        liquidated_wallet,
+        liquidator_wallet,
+        liquidator_collateral_spl,
+        liquidator_borrowed_spl,
+        repaid_borrow_amount,
+        min_collateral_amount,
+        borrowed_pool_id,
+        collateral_pool_id,
+    )
+}
+
+pub(crate) fn extern_liquidate_in(
+    keys: &DeploymentKeys,
+    liquidated_wallet: &Pubkey, // wallet key for account to be liquidated
+    liquidator_wallet: &Pubkey, // wallet key for liquidator, signer
+    liquidator_collateral_spl: &Pubkey, // liquidator's SPL token account for collateral asset
+    liquidator_borrowed_spl: &Pubkey, // liquidator's SPL token account for repaid asset
+    repaid_borrow_amount: u64,
+    min_collateral_amount: u64,
+    borrowed_pool_id: u8,
+    collateral_pool_id: u8,
 ) -> Instruction {
     extern_liquidate_full(
         liquidated_wallet,
         liquidator_wallet,
-        &consts::get_user_info_k(liquidated_wallet),
-        &consts::get_base_pda(),
+        &keys.user_info_k(liquidated_wallet),
+        &keys.base_pda,
         liquidator_collateral_spl,
         liquidator_borrowed_spl,
-        &consts::get_asset_pool_k(collateral_pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, collateral_pool_id),
-        &consts::get_asset_pool_k(borrowed_pool_id),
-        &consts::get_asset_pool_spl_k(&spl_token::ID, borrowed_pool_id),
-        &consts::get_pool_summaries_k(),
+        &keys.asset_pool_k(collateral_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, collateral_pool_id),
+        &keys.asset_pool_k(borrowed_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, borrowed_pool_id),
+        &keys.pool_summaries,
         &spl_token::ID,
-        &consts::program::ID,
+        &keys.program_id,
         repaid_borrow_amount,
         min_collateral_amount,
         borrowed_pool_id,
@@ -476,7 +764,7 @@ pub fn extern_liquidate_full(
     let mut buffer = vec![0; data_size];
     buffer[0] = consts::CMD_EXTERN_LIQUIDATE;
 
-    let mut param = mut_cast::<ExternLiquidateParam>(&mut buffer[1..]);
+    let param = mut_cast::<ExternLiquidateParam>(&mut buffer[1..]);
     param.repaid_borrow_amount = repaid_borrow_amount;
     param.min_collateral_amount = min_collateral_amount;
     param.borrowed_pool_id = borrowed_pool_id;
@@ -502,49 +790,799 @@ pub fn extern_liquidate_full(
     }
 }
 
+pub fn update_user_asset_config(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    update_user_asset_config_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        use_as_collateral,
+        pool_id,
+    )
+}
+
+pub(crate) fn update_user_asset_config_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    update_user_asset_config_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &keys.program_id,
+        use_as_collateral,
+        pool_id,
+    )
+}
+
+// toggles whether user's deposit in pool_id counts towards borrow power
+pub fn update_user_asset_config_full(
+    user_wallet: &Pubkey,     // user wallet account, needs to be signer
+    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
+    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey, // consts::get_price_summaries_k()
+    program_id: &Pubkey,      // consts::program::ID
+
+    use_as_collateral: bool,
+    pool_id: u8,
+) -> Instruction {
+    let data_size = 1 + std::mem::size_of::<UpdateUserAssetConfigParam>();
+    let mut buffer = vec![0; data_size];
+
+    buffer[0] = consts::CMD_UPDATE_USER_ASSET_CONFIG;
+    let param = mut_cast::<UpdateUserAssetConfigParam>(&mut buffer[1..]);
+    param.use_as_collateral = if use_as_collateral { 1 } else { 0 };
+    param.pool_id = pool_id;
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*user_wallet, true),
+            AccountMeta::new(*user_info, false),
+            AccountMeta::new_readonly(*pool_summaries, false),
+            AccountMeta::new_readonly(*price_summaries, false),
+        ],
+        data: buffer,
+    }
+}
+
+pub fn update_user_config(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    self_liquidation_threshold: u8,
+    post_self_liquidation_ratio_target: u8,
+    post_extern_liquidation_ratio_target: u8,
+) -> Instruction {
+    update_user_config_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        self_liquidation_threshold,
+        post_self_liquidation_ratio_target,
+        post_extern_liquidation_ratio_target,
+    )
+}
+
+pub(crate) fn update_user_config_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    self_liquidation_threshold: u8,
+    post_self_liquidation_ratio_target: u8,
+    post_extern_liquidation_ratio_target: u8,
+) -> Instruction {
+    update_user_config_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.program_id,
+        self_liquidation_threshold,
+        post_self_liquidation_ratio_target,
+        post_extern_liquidation_ratio_target,
+    )
+}
+
+// configures assist (auto-deleverage) for user, the three ratios are sent as single bytes like
+// TxMaker.update_user_config in js/src/apricot.js does. Contract rejects thresholds too close to
+// liquidation (ERR_SELF_DELEVERAGE_FACTOR_TOO_LARGE) and targets that are not below the threshold
+// (ERR_POST_DELEVERAGE_FACTOR_TOO_LARGE).
+pub fn update_user_config_full(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_info: &Pubkey,   // consts::get_user_info_k(user_wallet_key)
+    program_id: &Pubkey,  // consts::program::ID
+
+    self_liquidation_threshold: u8,
+    post_self_liquidation_ratio_target: u8,
+    post_extern_liquidation_ratio_target: u8,
+) -> Instruction {
+    let data_size = 1 + std::mem::size_of::<UpdateUserConfigParam>();
+    let mut buffer = vec![0; data_size];
+
+    buffer[0] = consts::CMD_UPDATE_USER_CONFIG;
+    let param = mut_cast::<UpdateUserConfigParam>(&mut buffer[1..]);
+    param.self_liquidation_threshold = self_liquidation_threshold;
+    param.post_self_liquidation_ratio_target = post_self_liquidation_ratio_target;
+    param.post_extern_liquidation_ratio_target = post_extern_liquidation_ratio_target;
+
+    Instruction {
+        program_id: *program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(*user_wallet, true),
+            AccountMeta::new(*user_info, false),
+        ],
+        data: buffer,
+    }
+}
+
+pub fn margin_swap(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    is_buy: bool,
+    sell_pool_id: u8,
+    sell_amount: u64,
+    buy_pool_id: u8,
+    buy_amount: u64,
+    swap_accounts: &SwapAccounts, // accounts of the DEX the swap goes through
+) -> Instruction {
+    margin_swap_in(
+        &DeploymentKeys::MAINNET,
+        user_wallet,
+        is_buy,
+        sell_pool_id,
+        sell_amount,
+        buy_pool_id,
+        buy_amount,
+        swap_accounts,
+    )
+}
+
+pub(crate) fn margin_swap_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    is_buy: bool,
+    sell_pool_id: u8,
+    sell_amount: u64,
+    buy_pool_id: u8,
+    buy_amount: u64,
+    swap_accounts: &SwapAccounts, // accounts of the DEX the swap goes through
+) -> Instruction {
+    margin_swap_full(
+        user_wallet,
+        true,
+        &keys.user_info_k(user_wallet),
+        &keys.base_pda,
+        &keys.asset_pool_k(sell_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, sell_pool_id),
+        &keys.asset_pool_k(buy_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, buy_pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &spl_token::ID,
+        &keys.program_id,
+        is_buy,
+        sell_pool_id,
+        sell_amount,
+        buy_pool_id,
+        buy_amount,
+        swap_accounts,
+    )
+}
+
+// swaps sell asset in user's portfolio into buy asset, borrowing sell asset if deposit is not enough
+pub fn margin_swap_full(
+    user_wallet: &Pubkey,        // user wallet account
+    user_wallet_is_signer: bool, // false only when the swap is executed on user's behalf
+    user_info: &Pubkey,          // consts::get_user_info_k(user_wallet_key)
+    base_pda: &Pubkey,           // consts::get_base_pda()
+
+    sell_asset_pool: &Pubkey,     // consts::get_asset_pool_k(sell_pool_id)
+    sell_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, sell_pool_id)
+
+    buy_asset_pool: &Pubkey,     // consts::get_asset_pool_k(buy_pool_id)
+    buy_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, buy_pool_id)
+
+    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey, // consts::get_price_summaries_k()
+    token_program: &Pubkey,
+    program_id: &Pubkey, // consts::program::ID
+
+    is_buy: bool,
+    sell_pool_id: u8,
+    sell_amount: u64,
+    buy_pool_id: u8,
+    buy_amount: u64,
+    swap_accounts: &SwapAccounts, // accounts of the DEX the swap goes through
+) -> Instruction {
+    let data_size = 1 + std::mem::size_of::<MarginSwapParam>();
+    let mut buffer = vec![0; data_size];
+
+    buffer[0] = consts::CMD_MARGIN_SWAP;
+    let param = mut_cast::<MarginSwapParam>(&mut buffer[1..]);
+    param.is_buy = if is_buy { 1 } else { 0 };
+    param.sell_amount = sell_amount;
+    param.buy_amount = buy_amount;
+    param.sell_pool_id = sell_pool_id;
+    param.buy_pool_id = buy_pool_id;
+    param.target_swap = swap_accounts.target_swap() as u8;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user_wallet, user_wallet_is_signer),
+        AccountMeta::new(*user_info, false),
+        AccountMeta::new_readonly(*base_pda, false),
+        AccountMeta::new(*sell_asset_pool, false),
+        AccountMeta::new(*sell_asset_pool_spl, false),
+        AccountMeta::new(*buy_asset_pool, false),
+        AccountMeta::new(*buy_asset_pool_spl, false),
+        AccountMeta::new(*pool_summaries, false),
+        AccountMeta::new_readonly(*price_summaries, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ];
+    accounts.extend(swap_accounts.keys());
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: buffer,
+    }
+}
+
+// None when lp_pool_id is not an LP pool
+pub fn lp_create(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Option<Instruction> {
+    lp_create_in(
+        &DeploymentKeys::MAINNET,
+        &PoolRegistry::mainnet(),
+        user_wallet,
+        lp_pool_id,
+        left_amount,
+        right_amount,
+        min_lp_amount,
+    )
+}
+
+pub(crate) fn lp_create_in(
+    keys: &DeploymentKeys,
+    pools: &PoolRegistry,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    left_amount: u64,
+    right_amount: u64,
+    min_lp_amount: u64,
+) -> Option<Instruction> {
+    let lp_info = pools.lp_info(lp_pool_id)?;
+    Some(lp_create_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.base_pda,
+        &keys.asset_pool_k(lp_info.left_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_info.left_pool_id),
+        &keys.asset_pool_k(lp_info.right_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_info.right_pool_id),
+        &keys.asset_pool_k(lp_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &spl_token::ID,
+        &keys.program_id,
+        lp_info.swap_info.target_swap(),
+        lp_info.left_pool_id,
+        left_amount,
+        lp_info.right_pool_id,
+        right_amount,
+        lp_pool_id,
+        min_lp_amount,
+        &lp_info.swap_info.deposit_keys(),
+    ))
+}
+
+// uses user's deposit of left and right asset to create LP token on target_swap, borrowing them if
+// deposit is not enough
+pub fn lp_create_full(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_info: &Pubkey,   // consts::get_user_info_k(user_wallet_key)
+    base_pda: &Pubkey,    // consts::get_base_pda()
+
+    left_asset_pool: &Pubkey,     // consts::get_asset_pool_k(left_pool_id)
+    left_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, left_pool_id)
+
+    right_asset_pool: &Pubkey, // consts::get_asset_pool_k(right_pool_id)
+    right_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, right_pool_id)
+
+    lp_asset_pool: &Pubkey,     // consts::get_asset_pool_k(lp_pool_id)
+    lp_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, lp_pool_id)
+
+    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey, // consts::get_price_summaries_k()
+    token_program: &Pubkey,
+    program_id: &Pubkey, // consts::program::ID
+
+    target_swap: SwapTarget,
+    left_pool_id: u8,
+    left_amount: u64,
+    right_pool_id: u8,
+    right_amount: u64,
+    lp_pool_id: u8,
+    min_lp_amount: u64,
+    swap_accounts: &[AccountMeta], // LpSwapInfo::deposit_keys()
+) -> Instruction {
+    let data_size = 1 + std::mem::size_of::<LpCreateParam>();
+    let mut buffer = vec![0; data_size];
+
+    buffer[0] = consts::CMD_LP_CREATE;
+    let param = mut_cast::<LpCreateParam>(&mut buffer[1..]);
+    param.left_amount = left_amount;
+    param.right_amount = right_amount;
+    param.min_lp_amount = min_lp_amount;
+    param.left_pool_id = left_pool_id;
+    param.right_pool_id = right_pool_id;
+    param.lp_pool_id = lp_pool_id;
+    param.target_swap = target_swap as u8;
+
+    let mut accounts = vec![
+        AccountMeta::new_readonly(*user_wallet, true),
+        AccountMeta::new(*user_info, false),
+        AccountMeta::new_readonly(*base_pda, false),
+        AccountMeta::new(*left_asset_pool, false),
+        AccountMeta::new(*left_asset_pool_spl, false),
+        AccountMeta::new(*right_asset_pool, false),
+        AccountMeta::new(*right_asset_pool_spl, false),
+        AccountMeta::new(*lp_asset_pool, false),
+        AccountMeta::new(*lp_asset_pool_spl, false),
+        AccountMeta::new(*pool_summaries, false),
+        AccountMeta::new_readonly(*price_summaries, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ];
+    accounts.extend_from_slice(swap_accounts);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: buffer,
+    }
+}
+
+pub fn lp_redeem(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    lp_amount: u64,
+    min_left_amount: u64,
+    min_right_amount: u64,
+) -> Option<Instruction> {
+    lp_redeem_in(
+        &DeploymentKeys::MAINNET,
+        &PoolRegistry::mainnet(),
+        user_wallet,
+        lp_pool_id,
+        lp_amount,
+        min_left_amount,
+        min_right_amount,
+    )
+}
+
+pub(crate) fn lp_redeem_in(
+    keys: &DeploymentKeys,
+    pools: &PoolRegistry,
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    lp_pool_id: u8,
+    lp_amount: u64,
+    min_left_amount: u64,
+    min_right_amount: u64,
+) -> Option<Instruction> {
+    let lp_info = pools.lp_info(lp_pool_id)?;
+    Some(lp_redeem_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.base_pda,
+        &keys.asset_pool_k(lp_info.left_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_info.left_pool_id),
+        &keys.asset_pool_k(lp_info.right_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_info.right_pool_id),
+        &keys.asset_pool_k(lp_pool_id),
+        &keys.asset_pool_spl_k(&spl_token::ID, lp_pool_id),
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &spl_token::ID,
+        &keys.program_id,
+        lp_info.swap_info.target_swap(),
+        lp_info.left_pool_id,
+        min_left_amount,
+        lp_info.right_pool_id,
+        min_right_amount,
+        lp_pool_id,
+        lp_amount,
+        &lp_info.swap_info.withdraw_keys(),
+    ))
+}
+
+// redeems user's LP token on target_swap back into left and right asset
+pub fn lp_redeem_full(
+    user_wallet: &Pubkey, // user wallet account, needs to be signer
+    user_info: &Pubkey,   // consts::get_user_info_k(user_wallet_key)
+    base_pda: &Pubkey,    // consts::get_base_pda()
+
+    left_asset_pool: &Pubkey,     // consts::get_asset_pool_k(left_pool_id)
+    left_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, left_pool_id)
+
+    right_asset_pool: &Pubkey, // consts::get_asset_pool_k(right_pool_id)
+    right_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, right_pool_id)
+
+    lp_asset_pool: &Pubkey,     // consts::get_asset_pool_k(lp_pool_id)
+    lp_asset_pool_spl: &Pubkey, // consts::get_asset_pool_spl_k(token_program, lp_pool_id)
+
+    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey, // consts::get_price_summaries_k()
+    token_program: &Pubkey,
+    program_id: &Pubkey, // consts::program::ID
+
+    target_swap: SwapTarget,
+    left_pool_id: u8,
+    min_left_amount: u64,
+    right_pool_id: u8,
+    min_right_amount: u64,
+    lp_pool_id: u8,
+    lp_amount: u64,
+    swap_accounts: &[AccountMeta], // LpSwapInfo::withdraw_keys()
+) -> Instruction {
+    let data_size = 1 + std::mem::size_of::<LpRedeemParam>();
+    let mut buffer = vec![0; data_size];
+
+    buffer[0] = consts::CMD_LP_REDEEM;
+    let param = mut_cast::<LpRedeemParam>(&mut buffer[1..]);
+    param.min_left_amount = min_left_amount;
+    param.min_right_amount = min_right_amount;
+    param.lp_amount = lp_amount;
+    param.left_pool_id = left_pool_id;
+    param.right_pool_id = right_pool_id;
+    param.lp_pool_id = lp_pool_id;
+    param.target_swap = target_swap as u8;
+
+    let mut accounts = vec![
+        AccountMeta::new(*user_wallet, true),
+        AccountMeta::new(*user_info, false),
+        AccountMeta::new_readonly(*base_pda, false),
+        AccountMeta::new(*left_asset_pool, false),
+        AccountMeta::new(*left_asset_pool_spl, false),
+        AccountMeta::new(*right_asset_pool, false),
+        AccountMeta::new(*right_asset_pool_spl, false),
+        AccountMeta::new(*lp_asset_pool, false),
+        AccountMeta::new(*lp_asset_pool_spl, false),
+        AccountMeta::new(*pool_summaries, false),
+        AccountMeta::new_readonly(*price_summaries, false),
+        AccountMeta::new_readonly(*token_program, false),
+    ];
+    accounts.extend_from_slice(swap_accounts);
+
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: buffer,
+    }
+}
+
+// LP operations need to sit between lp_op_check and lp_op_endcheck in the same transaction,
+// lp::build_lp_ops inserts both for you. sdk-ts publishes the two opcodes but not the accounts the
+// contract reads for them, so callers pass those in the order the contract expects and the
+// instruction data is the opcode alone.
+pub fn lp_op_check(check_accounts: &[AccountMeta]) -> Instruction {
+    lp_op_check_full(&consts::program::ID, check_accounts)
+}
+
+pub fn lp_op_endcheck(check_accounts: &[AccountMeta]) -> Instruction {
+    lp_op_endcheck_full(&consts::program::ID, check_accounts)
+}
+
+pub fn lp_op_check_full(
+    program_id: &Pubkey,            // consts::program::ID
+    check_accounts: &[AccountMeta], // accounts the contract checks the LP operations with
+) -> Instruction {
+    lp_op_bracket_full(consts::CMD_LP_OP_CHECK, program_id, check_accounts)
+}
+
+pub fn lp_op_endcheck_full(
+    program_id: &Pubkey,            // consts::program::ID
+    check_accounts: &[AccountMeta], // accounts the contract checks the LP operations with
+) -> Instruction {
+    lp_op_bracket_full(consts::CMD_LP_OP_ENDCHECK, program_id, check_accounts)
+}
+
+fn lp_op_bracket_full(cmd: u8, program_id: &Pubkey, check_accounts: &[AccountMeta]) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: check_accounts.to_vec(),
+        data: vec![cmd],
+    }
+}
+
 // APT Reward has one week accumalating period and one week vestin period,
 // Use this method to make any APT reward post-vesting available, contract will find how much APT
 // reward has finished vesting and make it available.
 pub fn make_lm_reward_claimable(user_wallet: &Pubkey) -> Instruction {
-    let user_info = consts::get_user_info_k(user_wallet);
-    let pool_summaries = consts::get_pool_summaries_k();
+    make_lm_reward_claimable_in(&DeploymentKeys::MAINNET, user_wallet)
+}
 
+pub(crate) fn make_lm_reward_claimable_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey,
+) -> Instruction {
+    make_lm_reward_claimable_full(
+        user_wallet,
+        &keys.user_info_k(user_wallet),
+        &keys.pool_summaries,
+        &keys.program_id,
+    )
+}
+
+pub fn make_lm_reward_claimable_full(
+    user_wallet: &Pubkey,    // user wallet account, needs to be signer
+    user_info: &Pubkey,      // consts::get_user_info_k(user_wallet_key)
+    pool_summaries: &Pubkey, // consts::get_pool_summaries_k()
+    program_id: &Pubkey,     // consts::program::ID
+) -> Instruction {
     let mut buffer = vec![0; 1];
     buffer[0] = consts::CMD_MAKE_LM_REWARD_AVAILABLE;
 
     Instruction {
-        program_id: consts::program::ID,
+        program_id: *program_id,
         accounts: vec![
             AccountMeta::new_readonly(*user_wallet, true),
             AccountMeta::new_readonly(*user_wallet, false),
-            AccountMeta::new(user_info, false),
-            AccountMeta::new(pool_summaries, false),
+            AccountMeta::new(*user_info, false),
+            AccountMeta::new(*pool_summaries, false),
         ],
         data: buffer,
     }
 }
 
 pub fn claim_apt_lm_reward(user_wallet: &Pubkey, user_apt_spl: &Pubkey) -> Instruction {
-    let base_pda = consts::get_base_pda();
-    let user_info = consts::get_user_info_k(user_wallet);
-    let lm_apt_vault = consts::lm_apt_vault::ID;
-    let pool_summaries = consts::get_pool_summaries_k();
-    let price_summaries = consts::get_price_summaries_k();
+    claim_apt_lm_reward_in(&DeploymentKeys::MAINNET, user_wallet, user_apt_spl)
+}
+
+pub(crate) fn claim_apt_lm_reward_in(
+    keys: &DeploymentKeys,
+    user_wallet: &Pubkey,
+    user_apt_spl: &Pubkey,
+) -> Instruction {
+    claim_apt_lm_reward_full(
+        user_wallet,
+        user_apt_spl,
+        &keys.base_pda,
+        &keys.user_info_k(user_wallet),
+        &keys.lm_apt_vault,
+        &keys.pool_summaries,
+        &keys.price_summaries,
+        &spl_token::ID,
+        &keys.program_id,
+    )
+}
 
+pub fn claim_apt_lm_reward_full(
+    user_wallet: &Pubkey,     // user wallet account, needs to be signer
+    user_apt_spl: &Pubkey,    // user's APT token account
+    base_pda: &Pubkey,        // consts::get_base_pda()
+    user_info: &Pubkey,       // consts::get_user_info_k(user_wallet_key)
+    lm_apt_vault: &Pubkey,    // consts::lm_apt_vault::ID
+    pool_summaries: &Pubkey,  // consts::get_pool_summaries_k()
+    price_summaries: &Pubkey, // consts::get_price_summaries_k()
+    token_program: &Pubkey,
+    program_id: &Pubkey, // consts::program::ID
+) -> Instruction {
     let mut buffer = vec![0; 1];
     buffer[0] = consts::CMD_CLAIM_APT_LM_REWARD;
     Instruction {
-        program_id: consts::program::ID,
+        program_id: *program_id,
         accounts: vec![
-            AccountMeta::new_readonly(base_pda, false),
+            AccountMeta::new_readonly(*base_pda, false),
             AccountMeta::new_readonly(*user_wallet, true),
-            AccountMeta::new(user_info, false),
+            AccountMeta::new(*user_info, false),
             AccountMeta::new(*user_apt_spl, false),
-            AccountMeta::new(lm_apt_vault, false),
-            AccountMeta::new(pool_summaries, false),
-            AccountMeta::new_readonly(price_summaries, false),
-            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new(*lm_apt_vault, false),
+            AccountMeta::new(*pool_summaries, false),
+            AccountMeta::new_readonly(*price_summaries, false),
+            AccountMeta::new_readonly(*token_program, false),
         ],
         data: buffer,
     }
 }
+
+#[cfg(test)]
+pub mod instructions_test {
+    use super::*;
+    use crate::config;
+    use crate::utils;
+
+    #[test]
+    fn test_update_user_asset_config_round_trip() {
+        let user_wallet = Pubkey::new_unique();
+        let ix = update_user_asset_config(&user_wallet, true, 12);
+
+        assert_eq!(ix.program_id, consts::program::ID);
+        assert_eq!(ix.data[0], consts::CMD_UPDATE_USER_ASSET_CONFIG);
+        assert_eq!(
+            ix.data.len(),
+            1 + std::mem::size_of::<UpdateUserAssetConfigParam>()
+        );
+        let param = utils::cast::<UpdateUserAssetConfigParam>(&ix.data[1..]);
+        assert_eq!(param.use_as_collateral, 1);
+        assert_eq!(param.pool_id, 12);
+
+        let ix = update_user_asset_config(&user_wallet, false, 3);
+        let param = utils::cast::<UpdateUserAssetConfigParam>(&ix.data[1..]);
+        assert_eq!(param.use_as_collateral, 0);
+        assert_eq!(param.pool_id, 3);
+
+        assert_eq!(ix.accounts.len(), 4);
+        assert_eq!(ix.accounts[0].pubkey, user_wallet);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, consts::get_user_info_k(&user_wallet));
+        assert!(ix.accounts[1].is_writable);
+    }
+
+    #[test]
+    fn test_update_user_config_layout() {
+        let user_wallet = Pubkey::new_unique();
+        let ix = update_user_config(&user_wallet, 85, 70, 60);
+
+        assert_eq!(ix.data, vec![consts::CMD_UPDATE_USER_CONFIG, 85, 70, 60]);
+        assert_eq!(ix.accounts.len(), 2);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, consts::get_user_info_k(&user_wallet));
+    }
+
+    #[test]
+    fn test_margin_swap_layout() {
+        let user_wallet = Pubkey::new_unique();
+        let lp_pool_id = config::sol_usdc_orca::POOL_ID;
+        let swap_accounts = PoolRegistry::mainnet()
+            .swap_accounts(lp_pool_id, config::usdc::POOL_ID, config::sol::POOL_ID)
+            .unwrap();
+        let ix = margin_swap(&user_wallet, true, 3, 1_000_000, 4, 50_000, &swap_accounts);
+
+        assert_eq!(ix.data.len(), 1 + 1 + 8 + 8 + 1 + 1 + 1);
+        assert_eq!(ix.data[0], consts::CMD_MARGIN_SWAP);
+        assert_eq!(ix.data[1], 1);
+        assert_eq!(ix.data[2..10], 1_000_000u64.to_le_bytes());
+        assert_eq!(ix.data[10..18], 50_000u64.to_le_bytes());
+        assert_eq!(ix.data[18..], [3, 4, consts::SWAP_ORCA]);
+
+        assert_eq!(ix.accounts.len(), 10 + 7);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(ix.accounts[1].pubkey, consts::get_user_info_k(&user_wallet));
+        assert_eq!(ix.accounts[3].pubkey, consts::get_asset_pool_k(3));
+        assert_eq!(ix.accounts[5].pubkey, consts::get_asset_pool_k(4));
+        assert_eq!(ix.accounts[10..], swap_accounts.keys()[..]);
+        // usdc is the right leg, token b of the orca swap
+        assert_eq!(
+            ix.accounts[13],
+            AccountMeta::new(config::sol_usdc_orca::SWAP_INFO.swap_token_b, false)
+        );
+    }
+
+    #[test]
+    fn test_swap_target_from_u8() {
+        for target in [
+            SwapTarget::Serum,
+            SwapTarget::Raydium,
+            SwapTarget::Saber,
+            SwapTarget::Mercurial,
+            SwapTarget::Orca,
+        ] {
+            assert_eq!(SwapTarget::from_u8(target as u8), Some(target));
+        }
+        assert_eq!(SwapTarget::from_u8(consts::SWAP_FAKE), None);
+    }
+
+    #[test]
+    fn test_lp_create_layout() {
+        let user_wallet = Pubkey::new_unique();
+        let lp_pool_id = config::sol_usdc_orca::POOL_ID;
+        let ix = lp_create(&user_wallet, lp_pool_id, 1_000, 2_000, 900).unwrap();
+
+        assert_eq!(ix.data.len(), 1 + 28);
+        assert_eq!(ix.data[0], consts::CMD_LP_CREATE);
+        assert_eq!(ix.data[1..9], 1_000u64.to_le_bytes());
+        assert_eq!(ix.data[9..17], 2_000u64.to_le_bytes());
+        assert_eq!(ix.data[17..25], 900u64.to_le_bytes());
+        assert_eq!(
+            ix.data[25..],
+            [
+                config::sol::POOL_ID,
+                config::usdc::POOL_ID,
+                lp_pool_id,
+                consts::SWAP_ORCA
+            ]
+        );
+
+        assert_eq!(ix.accounts.len(), 12 + 6);
+        assert!(ix.accounts[0].is_signer);
+        assert_eq!(
+            ix.accounts[3].pubkey,
+            consts::get_asset_pool_k(config::sol::POOL_ID)
+        );
+        assert_eq!(
+            ix.accounts[5].pubkey,
+            consts::get_asset_pool_k(config::usdc::POOL_ID)
+        );
+        assert_eq!(ix.accounts[7].pubkey, consts::get_asset_pool_k(lp_pool_id));
+        assert_eq!(ix.accounts[12].pubkey, consts::orca_swap_program::ID);
+        assert_eq!(ix.accounts[17].pubkey, config::sol_usdc_orca::ID);
+    }
+
+    #[test]
+    fn test_lp_redeem_swap_accounts() {
+        let user_wallet = Pubkey::new_unique();
+        let ix = lp_redeem(&user_wallet, config::ust_usdc_saber::POOL_ID, 500, 1, 2).unwrap();
+        assert_eq!(ix.data[0], consts::CMD_LP_REDEEM);
+        assert_eq!(ix.data[17..25], 500u64.to_le_bytes());
+        assert_eq!(
+            ix.data[25..27],
+            [config::ust::POOL_ID, config::usdc::POOL_ID]
+        );
+        assert_eq!(ix.data[28], consts::SWAP_SABER);
+        assert!(ix.accounts[0].is_writable);
+        assert_eq!(ix.accounts.len(), 12 + 9);
+
+        // raydium pools with serum queues need 3 more accounts on withdraw
+        let ix = lp_redeem(&user_wallet, config::sol_usdc_raydium::POOL_ID, 500, 1, 2).unwrap();
+        assert_eq!(ix.accounts.len(), 12 + 18);
+        let ix = lp_redeem(&user_wallet, config::srm_usdc_raydium::POOL_ID, 500, 1, 2).unwrap();
+        assert_eq!(ix.accounts.len(), 12 + 15);
+
+        assert!(lp_redeem(&user_wallet, config::usdc::POOL_ID, 500, 1, 2).is_none());
+    }
+
+    #[test]
+    fn test_smart_deposit() {
+        let user_wallet = Pubkey::new_unique();
+        let user_spl = Pubkey::new_unique();
+        let mut stats_data = vec![0u8; std::mem::size_of::<UserPagesStats>()];
+        let mut user_info_data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let strategy = PageStrategy::MostFree;
+
+        // nowhere to register
+        let stats = UserPagesStats::try_from_bytes(&stats_data).unwrap();
+        assert!(smart_deposit(&user_wallet, &user_spl, 10, 3, None, stats, strategy).is_none());
+
+        stats_data[2 * 8] = 2;
+        let stats = UserPagesStats::try_from_bytes(&stats_data).unwrap();
+        let ix = smart_deposit(&user_wallet, &user_spl, 10, 3, None, stats, strategy).unwrap();
+        assert_eq!(ix, add_user_and_deposit(&user_wallet, &user_spl, 10, 3, 8));
+
+        utils::try_cast_mut::<UserInfo>(&mut user_info_data)
+            .unwrap()
+            .page_id = 4;
+        let user_info = UserInfo::try_from_bytes(&user_info_data).unwrap();
+        let ix = smart_deposit(
+            &user_wallet,
+            &user_spl,
+            10,
+            3,
+            Some(user_info),
+            stats,
+            strategy,
+        )
+        .unwrap();
+        assert_eq!(ix, deposit(&user_wallet, &user_spl, 10, 3));
+
+        // users that left their page register again
+        utils::try_cast_mut::<UserInfo>(&mut user_info_data)
+            .unwrap()
+            .page_id = utils::INVALID_PAGE_ID;
+        let user_info = UserInfo::try_from_bytes(&user_info_data).unwrap();
+        let ix = smart_deposit(
+            &user_wallet,
+            &user_spl,
+            10,
+            3,
+            Some(user_info),
+            stats,
+            strategy,
+        )
+        .unwrap();
+        assert_eq!(ix.data[0], consts::CMD_ADD_USER_AND_DEPOSIT);
+    }
+}
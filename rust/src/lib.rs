@@ -1,5 +1,23 @@
+pub mod accrual;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod config;
 pub mod consts;
+pub mod decoder;
+pub mod env;
+pub mod error;
+pub mod health;
 pub mod instructions;
+pub mod liquidation;
+#[cfg(feature = "runtime-config")]
+pub mod loader;
+pub mod lp;
+pub mod reward;
+#[cfg(feature = "client")]
+pub mod snapshot;
 pub mod state;
+#[cfg(feature = "pubsub")]
+pub mod subscription;
+pub mod swap;
 pub mod utils;
+pub mod view;
@@ -0,0 +1,224 @@
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+
+use crate::config::PoolRegistry;
+use crate::consts;
+use crate::env::ApricotEnv;
+use crate::health::{self, HealthError};
+use crate::view::{AssetPoolView, UserInfoView};
+
+// Planning of extern_liquidate. The liquidator repays part of one borrow and receives collateral
+// worth the collateral pool's liquidation_discount more. A liquidation may only bring the user
+// back to their safe limit, asking for more fails with ERR_EXCEEDS_LIQUIDATION_LIMIT.
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LiquidationPlan {
+    pub borrowed_pool_id: u8,
+    pub collateral_pool_id: u8,
+    pub borrowed_mint: Pubkey,
+    pub collateral_mint: Pubkey,
+    pub repaid_borrow_amount: u64,
+    pub min_collateral_amount: u64,
+    // USD values
+    pub repaid_value: f64,
+    pub collateral_value: f64,
+}
+
+impl LiquidationPlan {
+    pub fn profit(&self) -> f64 {
+        self.collateral_value - self.repaid_value
+    }
+
+    // uses the liquidator's associated token accounts for both mints
+    pub fn instruction(
+        &self,
+        env: &ApricotEnv,
+        liquidated_wallet: &Pubkey,
+        liquidator_wallet: &Pubkey,
+    ) -> Instruction {
+        env.extern_liquidate(
+            liquidated_wallet,
+            liquidator_wallet,
+            &consts::get_associated_token_k(liquidator_wallet, &self.collateral_mint),
+            &consts::get_associated_token_k(liquidator_wallet, &self.borrowed_mint),
+            self.repaid_borrow_amount,
+            self.min_collateral_amount,
+            self.borrowed_pool_id,
+            self.collateral_pool_id,
+        )
+    }
+}
+
+// The most profitable liquidation of user, None when the user can't be liquidated.
+//
+// margin in [0, 1) is kept off both amounts: the repaid amount stays below the limit and
+// min_collateral_amount below the expected collateral, leaving room for interest accrued and
+// prices moved before the transaction lands.
+pub fn plan_liquidation(
+    user: &UserInfoView,
+    pools: &[AssetPoolView],
+    price_in_usd: impl Fn(u8) -> Option<f64>,
+    registry: &PoolRegistry,
+    margin: f64,
+) -> Result<Option<LiquidationPlan>, HealthError> {
+    let info = health::calculate_borrow_power(user, pools, &price_in_usd)?;
+    if !info.is_liquidatable() {
+        return Ok(None);
+    }
+    // room above the safe limit that liquidation may remove
+    let excess = info.total_borrow - info.safe_limit * info.max_borrow_allowed;
+
+    let mut best: Option<LiquidationPlan> = None;
+    for borrowed in user
+        .assets()
+        .iter()
+        .filter(|a| a.borrow_amount_native() > 0)
+    {
+        // calculate_borrow_power has checked that pools and prices exist
+        let borrowed_pool = find_pool(pools, borrowed.pool_id())?;
+        let borrowed_price = price(&price_in_usd, borrowed.pool_id())?;
+        let borrowed_value = borrowed.borrow_amount(borrowed_pool) * borrowed_price;
+
+        for collateral in user
+            .assets()
+            .iter()
+            .filter(|a| a.use_as_collateral() && a.deposit_amount_native() > 0)
+        {
+            let collateral_pool = find_pool(pools, collateral.pool_id())?;
+            let collateral_price = price(&price_in_usd, collateral.pool_id())?;
+            let deposit_value = collateral.deposit_amount(collateral_pool) * collateral_price;
+            // pools without a discount can't be liquidated at a profit
+            let discount = match registry.by_pool_id(collateral.pool_id()) {
+                Some(config) if config.liquidation_discount > 0.0 => config.liquidation_discount,
+                _ => continue,
+            };
+
+            // repaying r lowers borrows by r and borrow power by r * (1 + discount) * ltv
+            let improvement = 1.0 - info.safe_limit * (1.0 + discount) * collateral_pool.ltv();
+            if improvement <= 0.0 {
+                continue;
+            }
+            let repaid_value = (excess / improvement)
+                .min(borrowed_value)
+                .min(deposit_value / (1.0 + discount))
+                * (1.0 - margin);
+            if repaid_value <= 0.0 {
+                continue;
+            }
+            let collateral_value = repaid_value * (1.0 + discount);
+
+            let plan = LiquidationPlan {
+                borrowed_pool_id: borrowed.pool_id(),
+                collateral_pool_id: collateral.pool_id(),
+                borrowed_mint: *borrowed_pool.mint(),
+                collateral_mint: *collateral_pool.mint(),
+                repaid_borrow_amount: borrowed_pool.to_native_amount(repaid_value / borrowed_price),
+                min_collateral_amount: collateral_pool
+                    .to_native_amount(collateral_value * (1.0 - margin) / collateral_price),
+                repaid_value,
+                collateral_value,
+            };
+            let better = match &best {
+                Some(b) => plan.profit() > b.profit(),
+                None => true,
+            };
+            if better {
+                best = Some(plan);
+            }
+        }
+    }
+    Ok(best)
+}
+
+fn find_pool(pools: &[AssetPoolView], pool_id: u8) -> Result<&AssetPoolView, HealthError> {
+    pools
+        .iter()
+        .find(|pool| pool.pool_id() == pool_id)
+        .ok_or(HealthError::MissingPool { pool_id })
+}
+
+fn price(price_in_usd: impl Fn(u8) -> Option<f64>, pool_id: u8) -> Result<f64, HealthError> {
+    price_in_usd(pool_id).ok_or(HealthError::MissingPrice { pool_id })
+}
+
+#[cfg(test)]
+pub mod liquidation_test {
+    use super::*;
+    use crate::config::{PoolCategory, PoolConfig};
+    use crate::health::health_test::pool_view;
+    use crate::state::{RawAmt, UserInfo};
+    use crate::utils;
+
+    fn registry(liquidation_discount: f64) -> PoolRegistry {
+        PoolRegistry::new(vec![PoolConfig {
+            name: "BTC".to_string(),
+            pool_id: 0,
+            mint: Pubkey::new_unique(),
+            decimals: 6,
+            category: PoolCategory::Volatile,
+            liquidation_discount,
+            lp: None,
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_plan_liquidation() {
+        let mut data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user_info = utils::try_cast_mut::<UserInfo>(&mut data).unwrap();
+        user_info.num_assets = 2;
+        user_info.user_asset_info[0].pool_id = 0;
+        user_info.user_asset_info[0].use_as_collateral = 1;
+        user_info.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(1_000_000);
+        user_info.user_asset_info[1].pool_id = 3;
+        user_info.user_asset_info[1].borrow_amount = RawAmt::from_native_amount(60_000_000);
+        let user = UserInfoView::from(UserInfo::from_bytes(&data));
+
+        let pools = [pool_view(0, 0.5, 0.8), pool_view(3, 0.9, 0.9)];
+        let prices = |pool_id| match pool_id {
+            0 => Some(100.0),
+            3 => Some(1.0),
+            _ => None,
+        };
+        let plan = plan_liquidation(&user, &pools, prices, &registry(0.01), 0.0)
+            .unwrap()
+            .unwrap();
+        assert_eq!((plan.borrowed_pool_id, plan.collateral_pool_id), (3, 0));
+        // liquidation brings the user exactly back to the safe limit
+        let borrow_after = 60.0 - plan.repaid_value;
+        let borrow_power_after = 50.0 - plan.collateral_value * 0.5;
+        assert!((borrow_after / borrow_power_after - 0.8).abs() < 1e-9);
+        assert_eq!(plan.repaid_borrow_amount, 33_557_047);
+        assert!((plan.profit() - plan.repaid_value * 0.01).abs() < 1e-9);
+
+        let ix = plan.instruction(
+            &ApricotEnv::mainnet(),
+            &Pubkey::new_unique(),
+            &Pubkey::new_unique(),
+        );
+        assert_eq!(ix.data[0], consts::CMD_EXTERN_LIQUIDATE);
+
+        // healthy users can't be liquidated
+        let healthy = |pool_id| match pool_id {
+            0 => Some(200.0),
+            _ => Some(1.0),
+        };
+        assert_eq!(
+            plan_liquidation(&user, &pools, healthy, &registry(0.01), 0.0),
+            Ok(None)
+        );
+        // nor can force-assisted users below the liquidation limit
+        let force_assisted = |pool_id| match pool_id {
+            0 => Some(119.4),
+            _ => Some(1.0),
+        };
+        assert_eq!(
+            plan_liquidation(&user, &pools, force_assisted, &registry(0.01), 0.0),
+            Ok(None)
+        );
+        // nor can collateral without a discount
+        assert_eq!(
+            plan_liquidation(&user, &pools, prices, &registry(0.0), 0.0),
+            Ok(None)
+        );
+    }
+}
@@ -0,0 +1,366 @@
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::borrow::Cow;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use crate::config::{ApricotConfig, PoolCategory, PoolConfig, PoolRegistry, RegistryError};
+use crate::lp::{
+    LpInfo, LpSwapInfo, OrcaFarmInfo, OrcaLpSwapInfo, RaydiumLpSwapInfo, RaydiumReward,
+    RaydiumStakeInfo, SaberLpSwapInfo, SerumQueues,
+};
+
+// Reading ApricotConfig from JSON or TOML. Keys are base58 strings, the file mirrors the structs
+// in config and lp field by field:
+//
+//   program_id = "6UeJ..."
+//   base_pda = "..."
+//   ...
+//   [[pools]]
+//   name = "USDC"
+//   pool_id = 3
+//   mint = "EPjF..."
+//   decimals = 6
+//   category = "stable"
+//   liquidation_discount = 0.04
+//
+// LP pools add a [pools.lp] table with left_pool_id and right_pool_id, and a [pools.lp.swap] table
+// holding dex = "saber", "orca" or "raydium" and the keys of that swap.
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+    TomlDe(toml::de::Error),
+    TomlSer(toml::ser::Error),
+    InvalidKey { field: &'static str, value: String },
+    Registry(RegistryError),
+    // file extension is neither .json nor .toml
+    UnknownFormat,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "failed to read config: {}", err),
+            ConfigError::Json(err) => write!(f, "invalid JSON config: {}", err),
+            ConfigError::TomlDe(err) => write!(f, "invalid TOML config: {}", err),
+            ConfigError::TomlSer(err) => write!(f, "failed to write TOML config: {}", err),
+            ConfigError::InvalidKey { field, value } => {
+                write!(f, "{} is not a valid pubkey: {}", field, value)
+            }
+            ConfigError::Registry(err) => write!(f, "invalid pools: {}", err),
+            ConfigError::UnknownFormat => write!(f, "config file must end in .json or .toml"),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl ApricotConfig {
+    pub fn from_json_str(s: &str) -> Result<Self, ConfigError> {
+        let file: ConfigFile = serde_json::from_str(s).map_err(ConfigError::Json)?;
+        file.parse()
+    }
+
+    pub fn from_toml_str(s: &str) -> Result<Self, ConfigError> {
+        let file: ConfigFile = toml::from_str(s).map_err(ConfigError::TomlDe)?;
+        file.parse()
+    }
+
+    // format follows the extension
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).map_err(ConfigError::Io)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => ApricotConfig::from_json_str(&s),
+            Some("toml") => ApricotConfig::from_toml_str(&s),
+            _ => Err(ConfigError::UnknownFormat),
+        }
+    }
+
+    pub fn to_json_string(&self) -> Result<String, ConfigError> {
+        serde_json::to_string_pretty(&ConfigFile::from_info(self)).map_err(ConfigError::Json)
+    }
+
+    pub fn to_toml_string(&self) -> Result<String, ConfigError> {
+        toml::to_string(&ConfigFile::from_info(self)).map_err(ConfigError::TomlSer)
+    }
+}
+
+fn parse_key(field: &'static str, value: &str) -> Result<Pubkey, ConfigError> {
+    Pubkey::from_str(value).map_err(|_| ConfigError::InvalidKey {
+        field,
+        value: value.to_string(),
+    })
+}
+
+// conversion between a file struct and the struct it describes
+trait FileRepr: Sized {
+    type Info;
+    fn parse(&self) -> Result<Self::Info, ConfigError>;
+    fn from_info(info: &Self::Info) -> Self;
+}
+
+impl<T: FileRepr> FileRepr for Option<T> {
+    type Info = Option<T::Info>;
+
+    fn parse(&self) -> Result<Self::Info, ConfigError> {
+        self.as_ref().map(T::parse).transpose()
+    }
+
+    fn from_info(info: &Self::Info) -> Self {
+        info.as_ref().map(T::from_info)
+    }
+}
+
+// rewards of the built-in pools are borrowed from config, loaded ones are owned
+impl FileRepr for Vec<RaydiumRewardFile> {
+    type Info = Cow<'static, [RaydiumReward]>;
+
+    fn parse(&self) -> Result<Self::Info, ConfigError> {
+        let rewards = self
+            .iter()
+            .map(RaydiumRewardFile::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Cow::Owned(rewards))
+    }
+
+    fn from_info(info: &Self::Info) -> Self {
+        info.iter().map(RaydiumRewardFile::from_info).collect()
+    }
+}
+
+// file struct with the keys of $info as strings and the $nested fields as their file structs
+macro_rules! file_repr {
+    ($file:ident => $info:ident { $($key:ident),* $(,)? } $(nested { $($field:ident: $ty:ty),* $(,)? })?) => {
+        #[derive(Serialize, Deserialize)]
+        struct $file {
+            $($key: String,)*
+            $($($field: $ty,)*)?
+        }
+
+        impl FileRepr for $file {
+            type Info = $info;
+
+            fn parse(&self) -> Result<$info, ConfigError> {
+                Ok($info {
+                    $($key: parse_key(stringify!($key), &self.$key)?,)*
+                    $($($field: self.$field.parse()?,)*)?
+                })
+            }
+
+            fn from_info(info: &$info) -> Self {
+                $file {
+                    $($key: info.$key.to_string(),)*
+                    $($($field: <$ty>::from_info(&info.$field),)*)?
+                }
+            }
+        }
+    };
+}
+
+file_repr!(SaberFile => SaberLpSwapInfo {
+    lp_mint, swap, swap_authority, token_a_vault, token_b_vault, token_a_fees, token_b_fees,
+    quarry, rewarder, miner,
+});
+
+file_repr!(OrcaFarmFile => OrcaFarmInfo {
+    global_lp_vault, farm_token_mint, global_farm_state, global_reward_token_vault,
+    reward_token_authority, user_farm_state, user_reward_token_account,
+});
+
+file_repr!(OrcaFile => OrcaLpSwapInfo {
+    lp_mint, swap, swap_authority, swap_token_a, swap_token_b, fee_account,
+} nested {
+    farm: OrcaFarmFile,
+    double_dip: Option<OrcaFarmFile>,
+});
+
+file_repr!(SerumQueuesFile => SerumQueues { event_queue, bids, asks });
+
+file_repr!(RaydiumRewardFile => RaydiumReward { user_reward_account, reward_vault });
+
+file_repr!(RaydiumStakeFile => RaydiumStakeInfo {
+    stake_program, pool_id, pool_authority, pool_lp_vault, user_ledger,
+} nested {
+    rewards: Vec<RaydiumRewardFile>,
+});
+
+file_repr!(RaydiumFile => RaydiumLpSwapInfo {
+    lp_mint, amm_id, amm_authority, amm_open_orders, amm_target_orders, pool_coin_token,
+    pool_pc_token, pool_withdraw_queue, pool_temp_lp_token, serum_program, serum_market,
+    serum_coin_vault, serum_pc_vault, serum_vault_signer,
+} nested {
+    serum_queues: Option<SerumQueuesFile>,
+    stake: Option<RaydiumStakeFile>,
+});
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "dex", rename_all = "lowercase")]
+enum SwapFile {
+    Saber(SaberFile),
+    Orca(OrcaFile),
+    Raydium(RaydiumFile),
+}
+
+#[derive(Serialize, Deserialize)]
+struct LpFile {
+    left_pool_id: u8,
+    right_pool_id: u8,
+    swap: SwapFile,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CategoryFile {
+    Volatile,
+    Stable,
+    Lp,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PoolFile {
+    name: String,
+    pool_id: u8,
+    mint: String,
+    decimals: u8,
+    category: CategoryFile,
+    #[serde(default)]
+    liquidation_discount: f64,
+    lp: Option<LpFile>,
+}
+
+impl PoolFile {
+    fn parse(&self) -> Result<PoolConfig, ConfigError> {
+        let lp = match &self.lp {
+            Some(lp) => Some(LpInfo {
+                lp_pool_id: self.pool_id,
+                left_pool_id: lp.left_pool_id,
+                right_pool_id: lp.right_pool_id,
+                swap_info: match &lp.swap {
+                    SwapFile::Saber(info) => LpSwapInfo::Saber(info.parse()?),
+                    SwapFile::Orca(info) => LpSwapInfo::Orca(info.parse()?),
+                    SwapFile::Raydium(info) => LpSwapInfo::Raydium(info.parse()?),
+                },
+            }),
+            None => None,
+        };
+        Ok(PoolConfig {
+            name: self.name.clone(),
+            pool_id: self.pool_id,
+            mint: parse_key("mint", &self.mint)?,
+            decimals: self.decimals,
+            category: match self.category {
+                CategoryFile::Volatile => PoolCategory::Volatile,
+                CategoryFile::Stable => PoolCategory::Stable,
+                CategoryFile::Lp => PoolCategory::Lp,
+            },
+            liquidation_discount: self.liquidation_discount,
+            lp,
+        })
+    }
+
+    fn from_info(pool: &PoolConfig) -> Self {
+        PoolFile {
+            name: pool.name.clone(),
+            pool_id: pool.pool_id,
+            mint: pool.mint.to_string(),
+            decimals: pool.decimals,
+            category: match pool.category {
+                PoolCategory::Volatile => CategoryFile::Volatile,
+                PoolCategory::Stable => CategoryFile::Stable,
+                PoolCategory::Lp => CategoryFile::Lp,
+            },
+            liquidation_discount: pool.liquidation_discount,
+            lp: pool.lp.as_ref().map(|lp| LpFile {
+                left_pool_id: lp.left_pool_id,
+                right_pool_id: lp.right_pool_id,
+                swap: match &lp.swap_info {
+                    LpSwapInfo::Saber(info) => SwapFile::Saber(SaberFile::from_info(info)),
+                    LpSwapInfo::Orca(info) => SwapFile::Orca(OrcaFile::from_info(info)),
+                    LpSwapInfo::Raydium(info) => SwapFile::Raydium(RaydiumFile::from_info(info)),
+                },
+            }),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ConfigFile {
+    program_id: String,
+    base_pda: String,
+    price_pda: String,
+    pool_summaries: String,
+    price_summaries: String,
+    user_pages_stats: String,
+    lm_apt_vault: String,
+    #[serde(default)]
+    pools: Vec<PoolFile>,
+}
+
+impl ConfigFile {
+    fn parse(&self) -> Result<ApricotConfig, ConfigError> {
+        Ok(ApricotConfig {
+            program_id: parse_key("program_id", &self.program_id)?,
+            base_pda: parse_key("base_pda", &self.base_pda)?,
+            price_pda: parse_key("price_pda", &self.price_pda)?,
+            pool_summaries: parse_key("pool_summaries", &self.pool_summaries)?,
+            price_summaries: parse_key("price_summaries", &self.price_summaries)?,
+            user_pages_stats: parse_key("user_pages_stats", &self.user_pages_stats)?,
+            lm_apt_vault: parse_key("lm_apt_vault", &self.lm_apt_vault)?,
+            pools: PoolRegistry::new(
+                self.pools
+                    .iter()
+                    .map(PoolFile::parse)
+                    .collect::<Result<_, _>>()?,
+            )
+            .map_err(ConfigError::Registry)?,
+        })
+    }
+
+    fn from_info(config: &ApricotConfig) -> Self {
+        ConfigFile {
+            program_id: config.program_id.to_string(),
+            base_pda: config.base_pda.to_string(),
+            price_pda: config.price_pda.to_string(),
+            pool_summaries: config.pool_summaries.to_string(),
+            price_summaries: config.price_summaries.to_string(),
+            user_pages_stats: config.user_pages_stats.to_string(),
+            lm_apt_vault: config.lm_apt_vault.to_string(),
+            pools: config.pools.iter().map(PoolFile::from_info).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod loader_test {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let mainnet = ApricotConfig::mainnet();
+        let toml = mainnet.to_toml_string().unwrap();
+        assert_eq!(ApricotConfig::from_toml_str(&toml).unwrap(), mainnet);
+        let json = mainnet.to_json_string().unwrap();
+        assert_eq!(ApricotConfig::from_json_str(&json).unwrap(), mainnet);
+
+        let bad = toml.replacen(&mainnet.base_pda.to_string(), "not-a-key", 1);
+        match ApricotConfig::from_toml_str(&bad) {
+            Err(ConfigError::InvalidKey { field, .. }) => assert_eq!(field, "base_pda"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        // pools are checked like PoolRegistry::new does
+        let mut file = ConfigFile::from_info(&mainnet);
+        file.pools
+            .retain(|pool| pool.pool_id != crate::config::usdc::POOL_ID);
+        match file.parse() {
+            Err(ConfigError::Registry(RegistryError::MissingLpLeg { leg_pool_id, .. })) => {
+                assert_eq!(leg_pool_id, crate::config::usdc::POOL_ID)
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+}
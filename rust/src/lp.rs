@@ -0,0 +1,701 @@
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+use std::borrow::Cow;
+use std::fmt;
+
+use crate::config::{self, DeploymentKeys, PoolRegistry};
+use crate::consts;
+use crate::instructions::{self, SwapTarget};
+use crate::swap::{OrcaSwapAccounts, RaydiumSwapAccounts, SaberSwapAccounts, SwapAccounts};
+
+// Keys of the swaps behind our LP pools. Per-pool values live in config (SWAP_INFO of each LP
+// pool), the account lists below follow the order the contract forwards them to each DEX.
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SaberLpSwapInfo {
+    pub lp_mint: Pubkey,
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub token_a_vault: Pubkey,
+    pub token_b_vault: Pubkey,
+    pub token_a_fees: Pubkey,
+    pub token_b_fees: Pubkey,
+    // quarry we stake LP token into
+    pub quarry: Pubkey,
+    pub rewarder: Pubkey,
+    // quarry miner of base_pda, config::<pool>::miner
+    pub miner: Pubkey,
+}
+
+impl SaberLpSwapInfo {
+    pub fn deposit_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::saber_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.token_a_vault, false),
+            AccountMeta::new(self.token_b_vault, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    }
+
+    pub fn withdraw_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::saber_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.token_a_vault, false),
+            AccountMeta::new(self.token_b_vault, false),
+            AccountMeta::new(self.token_a_fees, false),
+            AccountMeta::new(self.token_b_fees, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    }
+
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::saber_quarry_program::ID, false),
+            AccountMeta::new(self.miner, false),
+            AccountMeta::new(self.quarry, false),
+            AccountMeta::new(
+                consts::get_associated_token_k(&self.miner, &self.lp_mint),
+                false,
+            ),
+            AccountMeta::new_readonly(spl_token::ID, false),
+            AccountMeta::new_readonly(self.rewarder, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    }
+
+    // vault and admin fee account of the left and the right leg, as getLRVaults in sdk-ts where
+    // USDT_USDC_SABER is the only pool with token b on the left
+    fn lr_vaults(&self) -> ((Pubkey, Pubkey), (Pubkey, Pubkey)) {
+        let a = (self.token_a_vault, self.token_a_fees);
+        let b = (self.token_b_vault, self.token_b_fees);
+        if self.lp_mint == config::usdt_usdc_saber::ID {
+            (b, a)
+        } else {
+            (a, b)
+        }
+    }
+
+    pub fn swap_accounts(&self, sell_left: bool) -> SaberSwapAccounts {
+        let (left, right) = self.lr_vaults();
+        let (source, destination) = if sell_left {
+            (left, right)
+        } else {
+            (right, left)
+        };
+        SaberSwapAccounts {
+            swap: self.swap,
+            swap_authority: self.swap_authority,
+            source_vault: source.0,
+            destination_vault: destination.0,
+            admin_fee_destination: destination.1,
+        }
+    }
+}
+
+// an orca aquafarm, base_pda stakes LP token into it and receives farm token in exchange
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrcaFarmInfo {
+    pub global_lp_vault: Pubkey,
+    pub farm_token_mint: Pubkey,
+    pub global_farm_state: Pubkey,
+    pub global_reward_token_vault: Pubkey,
+    pub reward_token_authority: Pubkey,
+    // farm accounts of base_pda, config::<pool>::user_farm_state(_2nd) and reward_*_account(_2nd)
+    pub user_farm_state: Pubkey,
+    pub user_reward_token_account: Pubkey,
+}
+
+impl OrcaFarmInfo {
+    // base_pda's farm token account, for double-dip pools this is config::<pool>::floating_lp_2nd
+    pub fn farm_token_account(&self) -> Pubkey {
+        consts::get_associated_token_k(&consts::base_pda::ID, &self.farm_token_mint)
+    }
+
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::orca_farm_program::ID, false),
+            AccountMeta::new(self.global_lp_vault, false),
+            AccountMeta::new(self.farm_token_mint, false),
+            AccountMeta::new(self.farm_token_account(), false),
+            AccountMeta::new(self.global_farm_state, false),
+            AccountMeta::new(self.user_farm_state, false),
+            AccountMeta::new(self.global_reward_token_vault, false),
+            AccountMeta::new(self.user_reward_token_account, false),
+            AccountMeta::new_readonly(self.reward_token_authority, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrcaLpSwapInfo {
+    pub lp_mint: Pubkey,
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    pub swap_token_a: Pubkey,
+    pub swap_token_b: Pubkey,
+    pub fee_account: Pubkey,
+    pub farm: OrcaFarmInfo,
+    // double-dip farm, staking the farm token received from `farm` a second time
+    pub double_dip: Option<OrcaFarmInfo>,
+}
+
+impl OrcaLpSwapInfo {
+    pub fn deposit_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::orca_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.swap_token_a, false),
+            AccountMeta::new(self.swap_token_b, false),
+            AccountMeta::new(self.lp_mint, false),
+        ]
+    }
+
+    pub fn withdraw_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::orca_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.swap_token_a, false),
+            AccountMeta::new(self.swap_token_b, false),
+            AccountMeta::new(self.fee_account, false),
+        ]
+    }
+
+    // double-dip pools are staked into both farms by the second stage only
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        match self.double_dip {
+            Some(_) => vec![],
+            None => self.farm.stake_keys(),
+        }
+    }
+
+    pub fn second_stake_keys(&self) -> Option<Vec<AccountMeta>> {
+        let double_dip = self.double_dip?;
+        let mut keys = self.farm.stake_keys();
+        keys.extend(double_dip.stake_keys());
+        Some(keys)
+    }
+
+    // token a is the left leg, as getLRVaults in sdk-ts
+    pub fn swap_accounts(&self, sell_left: bool) -> OrcaSwapAccounts {
+        let (source_vault, destination_vault) = if sell_left {
+            (self.swap_token_a, self.swap_token_b)
+        } else {
+            (self.swap_token_b, self.swap_token_a)
+        };
+        OrcaSwapAccounts {
+            swap: self.swap,
+            swap_authority: self.swap_authority,
+            source_vault,
+            destination_vault,
+            pool_mint: self.lp_mint,
+            fee_account: self.fee_account,
+        }
+    }
+}
+
+// only some serum markets need their queues passed in when raydium settles on withdraw
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SerumQueues {
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RaydiumReward {
+    // base_pda's reward account, config::<pool>::reward_ray_account / reward_second_account
+    pub user_reward_account: Pubkey,
+    pub reward_vault: Pubkey,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaydiumStakeInfo {
+    pub stake_program: Pubkey,
+    pub pool_id: Pubkey,
+    pub pool_authority: Pubkey,
+    pub pool_lp_vault: Pubkey,
+    // base_pda's ledger, config::<pool>::user_ledger_account
+    pub user_ledger: Pubkey,
+    // borrowed for the built-in pools, owned for the ones loaded at runtime
+    pub rewards: Cow<'static, [RaydiumReward]>,
+}
+
+impl RaydiumStakeInfo {
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        let mut keys = vec![
+            AccountMeta::new_readonly(self.stake_program, false),
+            AccountMeta::new(self.pool_id, false),
+            AccountMeta::new_readonly(self.pool_authority, false),
+            AccountMeta::new(self.user_ledger, false),
+            AccountMeta::new(self.pool_lp_vault, false),
+            AccountMeta::new(self.rewards[0].user_reward_account, false),
+            AccountMeta::new(self.rewards[0].reward_vault, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+            AccountMeta::new_readonly(spl_token::ID, false),
+        ];
+        for reward in &self.rewards[1..] {
+            keys.push(AccountMeta::new(reward.user_reward_account, false));
+            keys.push(AccountMeta::new(reward.reward_vault, false));
+        }
+        keys
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RaydiumLpSwapInfo {
+    pub lp_mint: Pubkey,
+    pub amm_id: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub pool_coin_token: Pubkey,
+    pub pool_pc_token: Pubkey,
+    pub pool_withdraw_queue: Pubkey,
+    pub pool_temp_lp_token: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+    pub serum_queues: Option<SerumQueues>,
+    // None for pools without a raydium farm
+    pub stake: Option<RaydiumStakeInfo>,
+}
+
+impl RaydiumLpSwapInfo {
+    pub fn deposit_keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::raydium_amm_program::ID, false),
+            AccountMeta::new(self.amm_id, false),
+            AccountMeta::new_readonly(self.amm_authority, false),
+            AccountMeta::new_readonly(self.amm_open_orders, false),
+            AccountMeta::new(self.amm_target_orders, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.pool_coin_token, false),
+            AccountMeta::new(self.pool_pc_token, false),
+            AccountMeta::new_readonly(self.serum_market, false),
+        ]
+    }
+
+    pub fn withdraw_keys(&self) -> Vec<AccountMeta> {
+        let mut keys = vec![
+            AccountMeta::new_readonly(consts::raydium_amm_program::ID, false),
+            AccountMeta::new(self.amm_id, false),
+            AccountMeta::new_readonly(self.amm_authority, false),
+            AccountMeta::new(self.amm_open_orders, false),
+            AccountMeta::new(self.amm_target_orders, false),
+            AccountMeta::new(self.lp_mint, false),
+            AccountMeta::new(self.pool_coin_token, false),
+            AccountMeta::new(self.pool_pc_token, false),
+            AccountMeta::new(self.pool_withdraw_queue, false),
+            AccountMeta::new(self.pool_temp_lp_token, false),
+            AccountMeta::new_readonly(self.serum_program, false),
+            AccountMeta::new(self.serum_market, false),
+            AccountMeta::new(self.serum_coin_vault, false),
+            AccountMeta::new(self.serum_pc_vault, false),
+            AccountMeta::new_readonly(self.serum_vault_signer, false),
+        ];
+        if let Some(queues) = self.serum_queues {
+            keys.push(AccountMeta::new(queues.event_queue, false));
+            keys.push(AccountMeta::new(queues.bids, false));
+            keys.push(AccountMeta::new(queues.asks, false));
+        }
+        keys
+    }
+
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        match &self.stake {
+            Some(stake) => stake.stake_keys(),
+            None => vec![],
+        }
+    }
+
+    // the amm swaps either way with the same accounts, None when the serum queues aren't listed
+    pub fn swap_accounts(&self) -> Option<RaydiumSwapAccounts> {
+        let queues = self.serum_queues?;
+        Some(RaydiumSwapAccounts {
+            amm_id: self.amm_id,
+            amm_authority: self.amm_authority,
+            amm_open_orders: self.amm_open_orders,
+            amm_target_orders: self.amm_target_orders,
+            pool_coin_token: self.pool_coin_token,
+            pool_pc_token: self.pool_pc_token,
+            serum_program: self.serum_program,
+            serum_market: self.serum_market,
+            serum_bids: queues.bids,
+            serum_asks: queues.asks,
+            serum_event_queue: queues.event_queue,
+            serum_coin_vault: self.serum_coin_vault,
+            serum_pc_vault: self.serum_pc_vault,
+            serum_vault_signer: self.serum_vault_signer,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LpSwapInfo {
+    Saber(SaberLpSwapInfo),
+    Orca(OrcaLpSwapInfo),
+    Raydium(RaydiumLpSwapInfo),
+}
+
+impl LpSwapInfo {
+    pub fn target_swap(&self) -> SwapTarget {
+        match self {
+            LpSwapInfo::Saber(_) => SwapTarget::Saber,
+            LpSwapInfo::Orca(_) => SwapTarget::Orca,
+            LpSwapInfo::Raydium(_) => SwapTarget::Raydium,
+        }
+    }
+
+    // accounts appended to lp_create
+    pub fn deposit_keys(&self) -> Vec<AccountMeta> {
+        match self {
+            LpSwapInfo::Saber(info) => info.deposit_keys(),
+            LpSwapInfo::Orca(info) => info.deposit_keys(),
+            LpSwapInfo::Raydium(info) => info.deposit_keys(),
+        }
+    }
+
+    // accounts appended to lp_redeem
+    pub fn withdraw_keys(&self) -> Vec<AccountMeta> {
+        match self {
+            LpSwapInfo::Saber(info) => info.withdraw_keys(),
+            LpSwapInfo::Orca(info) => info.withdraw_keys(),
+            LpSwapInfo::Raydium(info) => info.withdraw_keys(),
+        }
+    }
+
+    // farm accounts of the pool like getLpStakeKeys in sdk-ts, empty when the pool has no farm
+    // or is only staked through the second stage
+    pub fn stake_keys(&self) -> Vec<AccountMeta> {
+        match self {
+            LpSwapInfo::Saber(info) => info.stake_keys(),
+            LpSwapInfo::Orca(info) => info.stake_keys(),
+            LpSwapInfo::Raydium(info) => info.stake_keys(),
+        }
+    }
+
+    // accounts of a margin swap through the pool's DEX selling the left leg, or the right one
+    pub fn swap_accounts(&self, sell_left: bool) -> Option<SwapAccounts> {
+        match self {
+            LpSwapInfo::Saber(info) => Some(SwapAccounts::Saber(info.swap_accounts(sell_left))),
+            LpSwapInfo::Orca(info) => Some(SwapAccounts::Orca(info.swap_accounts(sell_left))),
+            LpSwapInfo::Raydium(info) => info.swap_accounts().map(SwapAccounts::Raydium),
+        }
+    }
+
+    // farm accounts of both stages like getSecondStakeKeys in sdk-ts, None unless the pool is
+    // double-dipped
+    pub fn second_stake_keys(&self) -> Option<Vec<AccountMeta>> {
+        match self {
+            LpSwapInfo::Orca(info) => info.second_stake_keys(),
+            _ => None,
+        }
+    }
+}
+
+// an LP pool together with the pools of the two assets it is made of
+#[derive(Clone, Debug, PartialEq)]
+pub struct LpInfo {
+    pub lp_pool_id: u8,
+    pub left_pool_id: u8,
+    pub right_pool_id: u8,
+    pub swap_info: LpSwapInfo,
+}
+
+// a single LP operation, see build_lp_ops
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LpAction {
+    Redeem {
+        lp_pool_id: u8,
+        lp_amount: u64,
+        min_left_amount: u64,
+        min_right_amount: u64,
+    },
+    Create {
+        lp_pool_id: u8,
+        left_amount: u64,
+        right_amount: u64,
+        min_lp_amount: u64,
+    },
+}
+
+impl LpAction {
+    pub fn lp_pool_id(&self) -> u8 {
+        match *self {
+            LpAction::Redeem { lp_pool_id, .. } | LpAction::Create { lp_pool_id, .. } => lp_pool_id,
+        }
+    }
+
+    // position in the lifecycle of an LP position, variants are declared in this order. Within
+    // one batch a pool is first unwound and then built up again, never the other way around.
+    fn stage(&self) -> u8 {
+        match self {
+            LpAction::Redeem { .. } => 0,
+            LpAction::Create { .. } => 1,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LpOpError {
+    NoActions,
+    NotLpPool { index: usize, pool_id: u8 },
+    // action at index unwinds a position an earlier action of the same pool has built up
+    OutOfOrder { index: usize, pool_id: u8 },
+}
+
+impl fmt::Display for LpOpError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            LpOpError::NoActions => write!(f, "no LP actions given"),
+            LpOpError::NotLpPool { index, pool_id } => {
+                write!(f, "action {}: pool {} is not an LP pool", index, pool_id)
+            }
+            LpOpError::OutOfOrder { index, pool_id } => write!(
+                f,
+                "action {}: pool {} is unwound after being built up",
+                index, pool_id
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LpOpError {}
+
+// pools of the deployment the instructions are built for
+fn validate_lp_ops(pools: &PoolRegistry, actions: &[LpAction]) -> Result<(), LpOpError> {
+    if actions.is_empty() {
+        return Err(LpOpError::NoActions);
+    }
+    // last stage seen for each pool
+    let mut stages = [None; 256];
+    for (index, action) in actions.iter().enumerate() {
+        let pool_id = action.lp_pool_id();
+        if pools.lp_info(pool_id).is_none() {
+            return Err(LpOpError::NotLpPool { index, pool_id });
+        }
+        let stage = action.stage();
+        if let Some(last) = stages[pool_id as usize] {
+            if stage < last {
+                return Err(LpOpError::OutOfOrder { index, pool_id });
+            }
+        }
+        stages[pool_id as usize] = Some(stage);
+    }
+    Ok(())
+}
+
+// builds the instructions for actions in the given order, bracketed by lp_op_check and
+// lp_op_endcheck with check_accounts. All of them have to go into the same transaction.
+pub fn build_lp_ops(
+    user_wallet: &Pubkey,
+    check_accounts: &[AccountMeta],
+    actions: &[LpAction],
+) -> Result<Vec<Instruction>, LpOpError> {
+    build_lp_ops_in(
+        &DeploymentKeys::MAINNET,
+        &PoolRegistry::mainnet(),
+        user_wallet,
+        check_accounts,
+        actions,
+    )
+}
+
+pub(crate) fn build_lp_ops_in(
+    keys: &DeploymentKeys,
+    pools: &PoolRegistry,
+    user_wallet: &Pubkey,
+    check_accounts: &[AccountMeta],
+    actions: &[LpAction],
+) -> Result<Vec<Instruction>, LpOpError> {
+    validate_lp_ops(pools, actions)?;
+
+    let mut ixs = Vec::with_capacity(actions.len() + 2);
+    ixs.push(instructions::lp_op_check_full(
+        &keys.program_id,
+        check_accounts,
+    ));
+    for (index, action) in actions.iter().enumerate() {
+        let ix = match *action {
+            LpAction::Redeem {
+                lp_pool_id,
+                lp_amount,
+                min_left_amount,
+                min_right_amount,
+            } => instructions::lp_redeem_in(
+                keys,
+                pools,
+                user_wallet,
+                lp_pool_id,
+                lp_amount,
+                min_left_amount,
+                min_right_amount,
+            ),
+            LpAction::Create {
+                lp_pool_id,
+                left_amount,
+                right_amount,
+                min_lp_amount,
+            } => instructions::lp_create_in(
+                keys,
+                pools,
+                user_wallet,
+                lp_pool_id,
+                left_amount,
+                right_amount,
+                min_lp_amount,
+            ),
+        };
+        ixs.push(ix.ok_or(LpOpError::NotLpPool {
+            index,
+            pool_id: action.lp_pool_id(),
+        })?);
+    }
+    ixs.push(instructions::lp_op_endcheck_full(
+        &keys.program_id,
+        check_accounts,
+    ));
+    Ok(ixs)
+}
+
+#[cfg(test)]
+pub mod lp_test {
+    use super::*;
+    use crate::config;
+
+    #[test]
+    fn test_build_lp_ops_brackets() {
+        let user_wallet = Pubkey::new_unique();
+        let lp_pool_id = config::ust_usdc_saber::POOL_ID;
+        let check_accounts = [
+            AccountMeta::new_readonly(user_wallet, true),
+            AccountMeta::new_readonly(sysvar::instructions::ID, false),
+        ];
+        let ixs = build_lp_ops(
+            &user_wallet,
+            &check_accounts,
+            &[
+                LpAction::Redeem {
+                    lp_pool_id,
+                    lp_amount: 20,
+                    min_left_amount: 9,
+                    min_right_amount: 9,
+                },
+                LpAction::Create {
+                    lp_pool_id,
+                    left_amount: 10,
+                    right_amount: 10,
+                    min_lp_amount: 19,
+                },
+            ],
+        )
+        .unwrap();
+
+        let cmds: Vec<u8> = ixs.iter().map(|ix| ix.data[0]).collect();
+        assert_eq!(
+            cmds,
+            [
+                consts::CMD_LP_OP_CHECK,
+                consts::CMD_LP_REDEEM,
+                consts::CMD_LP_CREATE,
+                consts::CMD_LP_OP_ENDCHECK
+            ]
+        );
+        assert_eq!(ixs[0].accounts, check_accounts);
+        assert_eq!(ixs[3].accounts, check_accounts);
+        assert_eq!(ixs[3].data, [consts::CMD_LP_OP_ENDCHECK]);
+    }
+
+    #[test]
+    fn test_build_lp_ops_rejects() {
+        let user_wallet = Pubkey::new_unique();
+        let saber = config::ust_usdc_saber::POOL_ID;
+
+        assert_eq!(
+            build_lp_ops(&user_wallet, &[], &[]),
+            Err(LpOpError::NoActions)
+        );
+        assert_eq!(
+            build_lp_ops(
+                &user_wallet,
+                &[],
+                &[
+                    LpAction::Create {
+                        lp_pool_id: saber,
+                        left_amount: 10,
+                        right_amount: 10,
+                        min_lp_amount: 0
+                    },
+                    LpAction::Redeem {
+                        lp_pool_id: saber,
+                        lp_amount: 20,
+                        min_left_amount: 0,
+                        min_right_amount: 0
+                    },
+                ]
+            ),
+            Err(LpOpError::OutOfOrder {
+                index: 1,
+                pool_id: saber
+            })
+        );
+        assert_eq!(
+            build_lp_ops(
+                &user_wallet,
+                &[],
+                &[LpAction::Redeem {
+                    lp_pool_id: config::usdc::POOL_ID,
+                    lp_amount: 20,
+                    min_left_amount: 0,
+                    min_right_amount: 0
+                }]
+            ),
+            Err(LpOpError::NotLpPool {
+                index: 0,
+                pool_id: config::usdc::POOL_ID
+            })
+        );
+    }
+
+    #[test]
+    fn test_farm_keys() {
+        let pools = PoolRegistry::mainnet();
+        let saber = pools.lp_info(config::ust_usdc_saber::POOL_ID).unwrap();
+        let keys = saber.swap_info.stake_keys();
+        assert_eq!(keys.len(), 7);
+        assert_eq!(keys[0].pubkey, consts::saber_quarry_program::ID);
+        assert_eq!(keys[1].pubkey, config::ust_usdc_saber::miner::ID);
+
+        let sol_usdc = pools.lp_info(config::sol_usdc_orca::POOL_ID).unwrap();
+        assert_eq!(sol_usdc.swap_info.stake_keys().len(), 10);
+        assert!(sol_usdc.swap_info.second_stake_keys().is_none());
+
+        // double-dip pools are only staked through the second stage
+        let msol_sol = pools.lp_info(config::msol_sol_orca::POOL_ID).unwrap();
+        assert!(msol_sol.swap_info.stake_keys().is_empty());
+        let keys = msol_sol.swap_info.second_stake_keys().unwrap();
+        assert_eq!(keys.len(), 10 + 10);
+        // farm token of the first farm is held in floating_lp_2nd
+        assert_eq!(keys[3].pubkey, config::msol_sol_orca::floating_lp_2nd::ID);
+        assert_eq!(keys[5].pubkey, config::msol_sol_orca::user_farm_state::ID);
+        assert_eq!(
+            keys[15].pubkey,
+            config::msol_sol_orca::user_farm_state_2nd::ID
+        );
+    }
+}
@@ -0,0 +1,167 @@
+use crate::accrual::{ProjectionError, SECONDS_PER_YEAR};
+use crate::state::{AssetPool, UserAssetInfo, UserInfo};
+
+// Liquidity mining rewards. APT accrues for a week through the pools' reward indices, then vests
+// for another week, after which make_lm_reward_claimable moves it to available_apt. The weeks
+// start at the user's last_vest_cutoff_timestamp. Amounts are native APT.
+//
+// apt_reward_per_year_per_deposit/borrow are taken as APT per year per native unit of the pool's
+// token, growing reward_deposit_index/reward_borrow_index linearly between updates. Neither the
+// SDKs nor AssetPool document that unit; projections are estimates in the same sense as accrual's.
+
+pub const LM_PERIOD: u64 = 7 * 24 * 3600;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct RewardProjection {
+    pub timestamp: u64,
+    // earned in the current week, vests after next_release_time
+    pub accruing_apt: f64,
+    // released at next_release_time
+    pub vesting_apt: f64,
+    // available after make_lm_reward_claimable at timestamp
+    pub claimable_apt: f64,
+    // retro rewards vest separately and are not part of the weekly cycle
+    pub retro_vesting_apt: f64,
+    pub next_release_time: u64,
+    // APT per year at the current deposits, borrows and reward rates
+    pub apt_per_year: f64,
+}
+
+impl RewardProjection {
+    // accruing_apt is only final at next_release_time, after that it vests for one more period
+    pub fn following_release_time(&self) -> u64 {
+        self.next_release_time + LM_PERIOD
+    }
+}
+
+fn reward_index_at(index: f64, per_year: f64, from: u64, to: u64) -> f64 {
+    index + per_year * to.saturating_sub(from) as f64 / SECONDS_PER_YEAR
+}
+
+// (APT earned but not settled into RewardInfo as of timestamp, APT per year)
+pub fn asset_reward(asset: &UserAssetInfo, pool: &AssetPool, timestamp: u64) -> (f64, f64) {
+    let deposit = { asset.deposit_amount }.to_native_amount() as f64;
+    let borrow = { asset.borrow_amount }.to_native_amount() as f64;
+    let deposit_index = reward_index_at(
+        pool.reward_deposit_index,
+        pool.apt_reward_per_year_per_deposit,
+        pool.last_update_time,
+        timestamp,
+    );
+    let borrow_index = reward_index_at(
+        pool.reward_borrow_index,
+        pool.apt_reward_per_year_per_borrow,
+        pool.last_update_time,
+        timestamp,
+    );
+    let earned = asset.reward_deposit_amount
+        + deposit * (deposit_index - asset.reward_deposit_index)
+        + asset.reward_borrow_amount
+        + borrow * (borrow_index - asset.reward_borrow_index);
+    let per_year = deposit * pool.apt_reward_per_year_per_deposit
+        + borrow * pool.apt_reward_per_year_per_borrow;
+    (earned, per_year)
+}
+
+pub fn project_rewards(
+    user: &UserInfo,
+    pools: &[AssetPool],
+    timestamp: u64,
+) -> Result<RewardProjection, ProjectionError> {
+    let reward = { user.reward };
+    let assets = { user.user_asset_info };
+    let num_assets = (user.num_assets as usize).min(assets.len());
+
+    // everything up to start is known, after that rewards accrue at apt_per_year
+    let start = timestamp.min(user.last_vest_cutoff_timestamp + LM_PERIOD);
+    let mut accruing_apt = reward.prev_week_apt;
+    let mut apt_per_year = 0.0;
+    for asset in &assets[..num_assets] {
+        let pool_id = asset.pool_id;
+        let pool = pools
+            .iter()
+            .find(|pool| pool.pool_id == pool_id)
+            .ok_or(ProjectionError::MissingPool { pool_id })?;
+        let (earned, per_year) = asset_reward(asset, pool, start);
+        accruing_apt += earned;
+        apt_per_year += per_year;
+    }
+
+    let mut vesting_apt = reward.vesting_apt;
+    let mut claimable_apt = reward.available_apt;
+    let mut cutoff = user.last_vest_cutoff_timestamp;
+    let mut accrued_until = start;
+    while cutoff + LM_PERIOD <= timestamp {
+        let release = cutoff + LM_PERIOD;
+        accruing_apt +=
+            apt_per_year * release.saturating_sub(accrued_until) as f64 / SECONDS_PER_YEAR;
+        accrued_until = accrued_until.max(release);
+        claimable_apt += vesting_apt;
+        vesting_apt = accruing_apt;
+        accruing_apt = 0.0;
+        cutoff = release;
+    }
+    accruing_apt +=
+        apt_per_year * timestamp.saturating_sub(accrued_until) as f64 / SECONDS_PER_YEAR;
+
+    Ok(RewardProjection {
+        timestamp,
+        accruing_apt,
+        vesting_apt,
+        claimable_apt,
+        retro_vesting_apt: { reward.vesting }.iter().sum(),
+        next_release_time: cutoff + LM_PERIOD,
+        apt_per_year,
+    })
+}
+
+#[cfg(test)]
+pub mod reward_test {
+    use super::*;
+    use crate::state::RawAmt;
+    use crate::utils;
+
+    #[test]
+    fn test_project_rewards() {
+        let mut pool_data = vec![0u8; std::mem::size_of::<AssetPool>()];
+        let pool = utils::try_cast_mut::<AssetPool>(&mut pool_data).unwrap();
+        pool.pool_id = 1;
+        pool.reward_deposit_index = 2.0;
+        pool.apt_reward_per_year_per_deposit = SECONDS_PER_YEAR / LM_PERIOD as f64;
+        pool.last_update_time = 0;
+        let pools = [*AssetPool::from_bytes(&pool_data)];
+
+        let mut user_data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user = utils::try_cast_mut::<UserInfo>(&mut user_data).unwrap();
+        user.num_assets = 1;
+        user.user_asset_info[0].pool_id = 1;
+        user.user_asset_info[0].deposit_amount = RawAmt::from_native_amount(10);
+        user.user_asset_info[0].reward_deposit_index = 1.5;
+        user.reward.vesting_apt = 7.0;
+        user.reward.available_apt = 1.0;
+        let user = *UserInfo::from_bytes(&user_data);
+
+        // half way through the first week: 10 * 0.5 from the index gap plus 10 * 0.5 accrued
+        let half = project_rewards(&user, &pools, LM_PERIOD / 2).unwrap();
+        assert_eq!(half.accruing_apt, 10.0);
+        assert_eq!(half.vesting_apt, 7.0);
+        assert_eq!(half.claimable_apt, 1.0);
+        assert_eq!(half.next_release_time, LM_PERIOD);
+        assert_eq!(
+            half.apt_per_year,
+            10.0 * SECONDS_PER_YEAR / LM_PERIOD as f64
+        );
+
+        // two weeks on the first week's rewards have vested and the second week's are vesting
+        let later = project_rewards(&user, &pools, 2 * LM_PERIOD).unwrap();
+        assert_eq!(later.claimable_apt, 1.0 + 7.0 + 15.0);
+        assert_eq!(later.vesting_apt, 10.0);
+        assert_eq!(later.accruing_apt, 0.0);
+        assert_eq!(later.next_release_time, 3 * LM_PERIOD);
+
+        assert_eq!(
+            project_rewards(&user, &[], LM_PERIOD),
+            Err(ProjectionError::MissingPool { pool_id: 1 })
+        );
+    }
+}
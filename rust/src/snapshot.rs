@@ -0,0 +1,256 @@
+use solana_program::pubkey::Pubkey;
+use solana_sdk::account::Account;
+use std::collections::BTreeMap;
+
+use crate::client::{self, ApricotClientError, ClientResult};
+use crate::config::PoolRegistry;
+use crate::env::ApricotEnv;
+use crate::health::{self, BorrowPowerInfo, HealthError};
+use crate::state::{AssetPool, AssetPrice, UserInfo};
+use crate::view::{AssetPoolView, UserInfoView};
+
+// A user, the pools they are in and the pools' prices, decoded from a single getMultipleAccounts response so every
+// account is from the same slot. See ApricotClient::portfolio_snapshot.
+#[derive(Clone, Debug)]
+pub struct PortfolioSnapshot {
+    pub slot: u64,
+    pub wallet: Pubkey,
+    // None when the wallet has no UserInfo account
+    pub user_info: Option<UserInfo>,
+    pub asset_pools: BTreeMap<u8, AssetPool>,
+    // pools of asset_pools that have an AssetPrice account
+    pub prices: BTreeMap<u8, AssetPrice>,
+}
+
+impl PortfolioSnapshot {
+    // UserInfo, then the AssetPool and then the AssetPrice of each of pool_ids, in the order decode
+    // expects. That is at most 1 + 2 * MAX_ASSETS_PER_USER keys, well within one
+    // getMultipleAccounts request.
+    pub fn keys(env: &ApricotEnv, wallet: &Pubkey, pool_ids: &[u8]) -> Vec<Pubkey> {
+        let mut keys = vec![env.user_info_k(wallet)];
+        keys.extend(pool_ids.iter().map(|pool_id| env.asset_pool_k(*pool_id)));
+        keys.extend(pool_ids.iter().map(|pool_id| env.asset_price_k(*pool_id)));
+        keys
+    }
+
+    // accounts as returned for keys(env, wallet, pool_ids)
+    pub fn decode(
+        env: &ApricotEnv,
+        wallet: &Pubkey,
+        slot: u64,
+        pool_ids: &[u8],
+        accounts: &[Option<Account>],
+    ) -> ClientResult<Self> {
+        let keys = PortfolioSnapshot::keys(env, wallet, pool_ids);
+        if accounts.len() != keys.len() {
+            return Err(ApricotClientError::AccountCount {
+                expected: keys.len(),
+                actual: accounts.len(),
+            });
+        }
+        let account = |i: usize| -> ClientResult<&Account> {
+            let account = accounts[i]
+                .as_ref()
+                .ok_or(ApricotClientError::AccountNotFound(keys[i]))?;
+            client::check_program_owner(env, &keys[i], account)?;
+            Ok(account)
+        };
+        let decode_err = |i: usize| {
+            let key = keys[i];
+            move |err| ApricotClientError::Decode { key, err }
+        };
+
+        let user_info = match accounts[0] {
+            Some(_) => Some(*UserInfo::try_from_bytes(&account(0)?.data).map_err(decode_err(0))?),
+            None => None,
+        };
+
+        let mut asset_pools = BTreeMap::new();
+        for (i, pool_id) in pool_ids.iter().enumerate() {
+            let pool =
+                AssetPool::try_from_bytes(&account(i + 1)?.data).map_err(decode_err(i + 1))?;
+            asset_pools.insert(*pool_id, *pool);
+        }
+
+        let mut prices = BTreeMap::new();
+        for (i, pool_id) in pool_ids.iter().enumerate() {
+            let i = 1 + pool_ids.len() + i;
+            if accounts[i].is_some() {
+                let price = AssetPrice::try_from_bytes(&account(i)?.data).map_err(decode_err(i))?;
+                prices.insert(*pool_id, *price);
+            }
+        }
+
+        Ok(PortfolioSnapshot {
+            slot,
+            wallet: *wallet,
+            user_info,
+            asset_pools,
+            prices,
+        })
+    }
+
+    // pools the user has a deposit or borrow in
+    pub fn user_pool_ids(&self) -> Vec<u8> {
+        match &self.user_info {
+            Some(user_info) => user_info.user_asset_info[..user_info.num_assets as usize]
+                .iter()
+                .map(|asset| asset.pool_id)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    // whether asset_pools holds every pool the user is in
+    pub fn is_complete(&self) -> bool {
+        self.user_pool_ids()
+            .iter()
+            .all(|pool_id| self.asset_pools.contains_key(pool_id))
+    }
+
+    // views of asset_pools in UI units of the registry's decimals, pools it doesn't list are left
+    // out
+    pub fn pool_views(&self, registry: &PoolRegistry) -> Vec<AssetPoolView> {
+        self.asset_pools
+            .iter()
+            .filter_map(|(pool_id, pool)| {
+                let config = registry.by_pool_id(*pool_id)?;
+                Some(AssetPoolView::new(pool, config.decimals))
+            })
+            .collect()
+    }
+
+    // AssetPrice::price_in_usd / price_scale, the SDKs read the raw u64 and don't publish its scale
+    pub fn price_in_usd(&self, pool_id: u8, price_scale: f64) -> Option<f64> {
+        self.prices
+            .get(&pool_id)
+            .map(|price| price.price_in_usd as f64 / price_scale)
+    }
+
+    // None when the wallet has no UserInfo, prices are the fetched ones as of price_in_usd
+    pub fn borrow_power(
+        &self,
+        registry: &PoolRegistry,
+        price_scale: f64,
+    ) -> Result<Option<BorrowPowerInfo>, HealthError> {
+        self.borrow_power_with(registry, |pool_id| self.price_in_usd(pool_id, price_scale))
+    }
+
+    // borrow_power with prices from elsewhere, as for health::calculate_borrow_power
+    pub fn borrow_power_with(
+        &self,
+        registry: &PoolRegistry,
+        price_in_usd: impl Fn(u8) -> Option<f64>,
+    ) -> Result<Option<BorrowPowerInfo>, HealthError> {
+        let user_info = match &self.user_info {
+            Some(user_info) => UserInfoView::from(user_info),
+            None => return Ok(None),
+        };
+        let pools = self.pool_views(registry);
+        health::calculate_borrow_power(&user_info, &pools, price_in_usd).map(Some)
+    }
+}
+
+#[cfg(test)]
+pub mod snapshot_test {
+    use super::*;
+    use crate::client::mock::MockRpc;
+    use crate::client::ApricotClient;
+    use crate::consts;
+    use crate::utils;
+    use solana_rpc_client_api::request::RpcRequest;
+
+    #[tokio::test]
+    async fn test_portfolio_snapshot() {
+        let mock = MockRpc::new();
+        let client = ApricotClient::with_rpc(mock.rpc_client(), ApricotEnv::mainnet());
+        let wallet = Pubkey::new_unique();
+        mock.set_slot(77);
+
+        // no UserInfo yet, a single request
+        let snapshot = client.portfolio_snapshot(&wallet).await.unwrap();
+        assert!(snapshot.user_info.is_none());
+        assert!(snapshot
+            .borrow_power(&client.env().config.pools, 1e6)
+            .unwrap()
+            .is_none());
+        assert_eq!(mock.requests(), vec![RpcRequest::GetMultipleAccounts]);
+
+        let mut user_info = vec![0u8; std::mem::size_of::<UserInfo>()];
+        {
+            let user_info = utils::try_cast_mut::<UserInfo>(&mut user_info).unwrap();
+            user_info.num_assets = 2;
+            user_info.user_asset_info[0].pool_id = 5;
+            user_info.user_asset_info[1].pool_id = 3;
+        }
+        mock.set_data(
+            consts::get_user_info_k(&wallet),
+            consts::program::ID,
+            user_info,
+        );
+        for pool_id in [3, 5] {
+            let mut pool = vec![0u8; std::mem::size_of::<AssetPool>()];
+            utils::try_cast_mut::<AssetPool>(&mut pool).unwrap().pool_id = pool_id;
+            mock.set_data(consts::get_asset_pool_k(pool_id), consts::program::ID, pool);
+        }
+        mock.set_data(
+            consts::get_asset_price_k(5),
+            consts::program::ID,
+            150_000_000u64.to_le_bytes().to_vec(),
+        );
+
+        // one request to find the pools and one to read them
+        let snapshot = client.portfolio_snapshot(&wallet).await.unwrap();
+        assert_eq!(mock.requests().len(), 3);
+        assert_eq!(snapshot.slot, 77);
+        assert_eq!(snapshot.user_pool_ids(), vec![5, 3]);
+        assert_eq!(
+            snapshot.asset_pools.keys().copied().collect::<Vec<_>>(),
+            vec![3, 5]
+        );
+        assert_eq!(snapshot.price_in_usd(5, 1e6), Some(150.0));
+        assert_eq!(snapshot.price_in_usd(3, 1e6), None);
+        assert_eq!(
+            snapshot.borrow_power(&client.env().config.pools, 1e6).err(),
+            Some(HealthError::MissingPrice { pool_id: 3 })
+        );
+        mock.set_data(
+            consts::get_asset_price_k(3),
+            consts::program::ID,
+            1_000_000u64.to_le_bytes().to_vec(),
+        );
+        let snapshot = client.portfolio_snapshot(&wallet).await.unwrap();
+        let borrow_power = snapshot
+            .borrow_power(&client.env().config.pools, 1e6)
+            .unwrap()
+            .unwrap();
+        assert_eq!(borrow_power.total_borrow, 0.0);
+        assert_eq!(
+            snapshot
+                .borrow_power_with(&client.env().config.pools, |_| None)
+                .err(),
+            Some(HealthError::MissingPrice { pool_id: 5 })
+        );
+
+        mock.remove_account(&consts::get_asset_pool_k(3));
+        match client.portfolio_snapshot(&wallet).await {
+            Err(ApricotClientError::AccountNotFound(key)) => {
+                assert_eq!(key, consts::get_asset_pool_k(3))
+            }
+            other => panic!("unexpected {:?}", other.map(|s| s.slot)),
+        }
+    }
+
+    #[test]
+    fn test_decode_checks_account_count() {
+        let env = ApricotEnv::mainnet();
+        let wallet = Pubkey::new_unique();
+        assert_eq!(PortfolioSnapshot::keys(&env, &wallet, &[3, 5]).len(), 5);
+        match PortfolioSnapshot::decode(&env, &wallet, 0, &[3, 5], &[None, None, None]) {
+            Err(ApricotClientError::AccountCount { expected, actual }) => {
+                assert_eq!((expected, actual), (5, 3))
+            }
+            other => panic!("unexpected {:?}", other.map(|s| s.slot)),
+        }
+    }
+}
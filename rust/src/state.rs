@@ -1,4 +1,6 @@
-use crate::utils;
+use crate::consts;
+use crate::error::ApricotDecodeError;
+use crate::utils::{self, Pod};
 use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
 use std::cell::Ref;
 use std::fmt::{Display, Formatter, Result as FormatResult};
@@ -6,7 +8,7 @@ use std::ops::{Add, Mul, Sub};
 
 pub const NATIVE_RAW_SHIFT: usize = 24;
 
-#[repr(packed)]
+#[repr(C, packed)]
 #[derive(Copy, Clone, Eq, PartialEq, PartialOrd, Ord, Debug)]
 /**
  * RawAmt to accrue interest with boosted precision
@@ -15,15 +17,30 @@ pub struct RawAmt {
     amt: u128,
 }
 
+unsafe impl Pod for RawAmt {}
+
 impl RawAmt {
+    pub fn from_native_amount(native_amount: u64) -> Self {
+        RawAmt {
+            amt: (native_amount as u128) << NATIVE_RAW_SHIFT,
+        }
+    }
+
     pub fn to_native_amount(&self) -> u64 {
         (self.amt >> NATIVE_RAW_SHIFT) as u64
     }
+
+    // used to accrue interest, precision is that of f64
+    pub fn scaled(&self, factor: f64) -> Self {
+        RawAmt {
+            amt: (self.amt as f64 * factor) as u128,
+        }
+    }
 }
 
 pub const MAX_ASSETS_PER_USER: usize = 16;
 
-#[repr(packed)]
+#[repr(C, packed)]
 #[derive(Copy, Clone, Debug)]
 pub struct UserAssetInfo {
     pub pool_id: u8,
@@ -42,8 +59,9 @@ pub struct UserAssetInfo {
     pub reward_borrow_index: f64,
 }
 
+unsafe impl Pod for UserAssetInfo {}
+
 impl Display for UserAssetInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(
             f,
@@ -53,18 +71,18 @@ impl Display for UserAssetInfo {
         writeln!(
             f,
             "deposit_native_amount: {}, deposit_native_interest: {}, deposit_apt_reward_native_amount: {}",
-            self.deposit_amount.to_native_amount(), self.deposit_interests, self.reward_deposit_amount
+            self.deposit_amount.to_native_amount(), { self.deposit_interests }, { self.reward_deposit_amount }
         )?;
         writeln!(
             f,
             "borrow_native_amount: {}, borrow_native_interest: {}, borrow_apt_reward_native_amount: {}",
-            self.borrow_amount.to_native_amount(), self.borrow_interests, self.reward_borrow_amount
+            self.borrow_amount.to_native_amount(), { self.borrow_interests }, { self.reward_borrow_amount }
         )?;
         Ok(())
     }
 }
 
-#[repr(packed)]
+#[repr(C, packed)]
 #[derive(Copy, Clone, Debug)]
 pub struct RewardInfo {
     pub vesting: [f64; 4], // retro vesting
@@ -78,28 +96,30 @@ pub struct RewardInfo {
     pub available_wluna: f64,    // wormhole luna
 }
 
+unsafe impl Pod for RewardInfo {}
+
 impl Display for RewardInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(
             f,
             "earning_apt: {}, vesting_apt: {}",
-            self.prev_week_apt, self.vesting_apt
+            { self.prev_week_apt },
+            { self.vesting_apt }
         )?;
         writeln!(
             f,
             "available_apt: {}, available_mnde: {}, available_wldo: {}, available_b180socn: {}, available_wluna: {}",
-            self.available_apt,
-            self.available_mnde,
-            self.available_wldo,
-            self.available_b180socn,
-            self.available_wluna
+            { self.available_apt },
+            { self.available_mnde },
+            { self.available_wldo },
+            { self.available_b180socn },
+            { self.available_wluna }
         )?;
         Ok(())
     }
 }
 
-#[repr(packed)]
+#[repr(C, packed)]
 #[derive(Copy, Clone, Debug)]
 pub struct UserInfo {
     pub page_id: u16,
@@ -111,27 +131,56 @@ pub struct UserInfo {
     pub last_update_timestamp: u64,
 }
 
+unsafe impl Pod for UserInfo {}
+
 impl UserInfo {
     pub fn from_account_info<'a>(acc_info: &'a AccountInfo) -> Result<Ref<'a, Self>, ProgramError> {
-        let data_ref = acc_info.try_borrow_data()?;
-        let bytes_ref = Ref::map(data_ref, |d| *d);
-        let user_info = Ref::map(bytes_ref, |b| utils::cast::<UserInfo>(b));
-        Ok(user_info)
+        Ok(utils::borrow_account::<Self>(acc_info)?)
     }
 
     pub fn from_bytes(data: &[u8]) -> &Self {
         utils::cast::<Self>(data)
     }
+
+    // Apricot accounts carry no discriminator, so on top of the length we check the owner and the
+    // fields that have a bounded range
+    pub fn try_from_account_info<'a>(
+        acc_info: &'a AccountInfo,
+    ) -> Result<Ref<'a, Self>, ApricotDecodeError> {
+        utils::check_owner(acc_info, &consts::program::ID)?;
+        let user_info = utils::borrow_account::<Self>(acc_info)?;
+        user_info.validate()?;
+        Ok(user_info)
+    }
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<&Self, ApricotDecodeError> {
+        let user_info = utils::try_cast::<Self>(data)?;
+        user_info.validate()?;
+        Ok(user_info)
+    }
+
+    // inactive users have withdrawn everything and left their users page, they register again on
+    // their next deposit
+    pub fn is_active(&self) -> bool {
+        self.page_id != utils::INVALID_PAGE_ID
+    }
+
+    fn validate(&self) -> Result<(), ApricotDecodeError> {
+        if self.num_assets as usize > MAX_ASSETS_PER_USER {
+            return Err(ApricotDecodeError::InvalidField {
+                field: "num_assets",
+                value: self.num_assets as u64,
+            });
+        }
+        Ok(())
+    }
 }
 
 impl Display for UserInfo {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
-        writeln!(
-            f,
-            "page_id: {}, num_assets: {}",
-            self.page_id, self.num_assets
-        )?;
+        writeln!(f, "page_id: {}, num_assets: {}", { self.page_id }, {
+            self.num_assets
+        })?;
         for i in 0..self.num_assets as usize {
             writeln!(f, "user_asset_info: {}", self.user_asset_info[i])?;
         }
@@ -139,14 +188,15 @@ impl Display for UserInfo {
         writeln!(
             f,
             "last_vest_cutoff_timestamp: {}, last_update_timestamp: {}",
-            self.last_vest_cutoff_timestamp, self.last_update_timestamp
+            { self.last_vest_cutoff_timestamp },
+            { self.last_update_timestamp }
         )?;
         Ok(())
     }
 }
 
 pub const TOKEN_NAME_SIZE: usize = 32;
-#[repr(packed)]
+#[repr(C, packed)]
 #[derive(Copy, Clone, Debug)]
 pub struct AssetPool {
     pub token_name: [u8; TOKEN_NAME_SIZE],
@@ -213,18 +263,28 @@ pub struct AssetPool {
     pub farm_yield: f64,
 }
 
+unsafe impl Pod for AssetPool {}
+
 impl AssetPool {
     pub fn from_account_info<'a>(acc_info: &'a AccountInfo) -> Result<Ref<'a, Self>, ProgramError> {
-        let data_ref = acc_info.try_borrow_data()?;
-        let bytes_ref = Ref::map(data_ref, |d| *d);
-        let asset_pool = Ref::map(bytes_ref, |b| utils::cast::<AssetPool>(b));
-        Ok(asset_pool)
+        Ok(utils::borrow_account::<Self>(acc_info)?)
     }
 
     pub fn from_bytes(data: &[u8]) -> &Self {
         utils::cast::<Self>(data)
     }
 
+    pub fn try_from_account_info<'a>(
+        acc_info: &'a AccountInfo,
+    ) -> Result<Ref<'a, Self>, ApricotDecodeError> {
+        utils::check_owner(acc_info, &consts::program::ID)?;
+        utils::borrow_account::<Self>(acc_info)
+    }
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<&Self, ApricotDecodeError> {
+        utils::try_cast::<Self>(data)
+    }
+
     pub fn calculate_new_interest_rate(
         self,
         deposit_native_amt: u64,
@@ -274,41 +334,149 @@ impl AssetPool {
 }
 
 impl Display for AssetPool {
-    #[allow(unaligned_references)]
     fn fmt(&self, f: &mut Formatter<'_>) -> FormatResult {
         writeln!(f, "pool_id: {}", self.pool_id)?;
         writeln!(f, "mint: {}", self.mint_key)?;
-        writeln!(f, "last_update_time: {}", self.last_update_time)?;
-        writeln!(f, "ltv: {}", self.ltv)?;
-        writeln!(f, "safe_factor: {}", self.safe_factor)?;
-        writeln!(f, "deposit_cap: {}", self.deposit_cap)?;
+        writeln!(f, "last_update_time: {}", { self.last_update_time })?;
+        writeln!(f, "ltv: {}", { self.ltv })?;
+        writeln!(f, "safe_factor: {}", { self.safe_factor })?;
+        writeln!(f, "deposit_cap: {}", { self.deposit_cap })?;
         writeln!(
             f,
             "deposit_amount: {}",
             self.deposit_amount.to_native_amount()
         )?;
-        writeln!(f, "deposit_interest_rate: {}", self.current_deposit_rate)?;
-        writeln!(
-            f,
-            "deposit_apt_reward_amount_per_year: {}",
+        writeln!(f, "deposit_interest_rate: {}", {
+            self.current_deposit_rate
+        })?;
+        writeln!(f, "deposit_apt_reward_amount_per_year: {}", {
             self.deposit_apt_reward_amount_per_year
-        )?;
+        })?;
         writeln!(
             f,
             "borrow_amount: {}",
             self.borrow_amount.to_native_amount()
         )?;
-        writeln!(f, "borrow_interest_rate: {}", self.current_borrow_rate)?;
-        writeln!(
-            f,
-            "borrow_apt_reward_amount_per_year: {}",
+        writeln!(f, "borrow_interest_rate: {}", { self.current_borrow_rate })?;
+        writeln!(f, "borrow_apt_reward_amount_per_year: {}", {
             self.borrow_apt_reward_amount_per_year
-        )?;
-        writeln!(f, "farm_yield: {}", self.farm_yield)?;
+        })?;
+        writeln!(f, "farm_yield: {}", { self.farm_yield })?;
         Ok(())
     }
 }
 
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug)]
+pub struct PoolListHeader {
+    pub num_pools: u16,
+    pub padding: [u8; 6],
+}
+
+unsafe impl Pod for PoolListHeader {}
+
+// mint keys of all listed pools, indexed by pool_id
+#[derive(Copy, Clone, Debug)]
+pub struct PoolList<'a> {
+    mint_keys: &'a [Pubkey],
+}
+
+impl<'a> PoolList<'a> {
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ApricotDecodeError> {
+        let header = utils::try_cast::<PoolListHeader>(data)?;
+        let num_pools = header.num_pools as usize;
+        let offset = std::mem::size_of::<PoolListHeader>();
+        let expected = offset + num_pools * std::mem::size_of::<Pubkey>();
+        if data.len() < expected {
+            return Err(ApricotDecodeError::TooShort {
+                expected,
+                actual: data.len(),
+            });
+        }
+        let mint_keys = utils::try_cast_slice::<Pubkey>(&data[offset..expected])?;
+        Ok(PoolList { mint_keys })
+    }
+
+    pub fn len(&self) -> usize {
+        self.mint_keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mint_keys.is_empty()
+    }
+
+    pub fn mint_keys(&self) -> &'a [Pubkey] {
+        self.mint_keys
+    }
+
+    pub fn get(&self, pool_id: u8) -> Option<&'a Pubkey> {
+        self.mint_keys.get(pool_id as usize)
+    }
+
+    pub fn pool_id_of(&self, mint_key: &Pubkey) -> Option<u8> {
+        self.mint_keys
+            .iter()
+            .position(|k| k == mint_key)
+            .map(|i| i as u8)
+    }
+}
+
+// PoolSummaries and PriceSummaries (consts::get_pool_summaries_k, get_price_summaries_k) have no
+// parser here: js/, sdk-ts and the contract samples only pass them through as accounts and no
+// published source gives their layout. Per-pool values are read from AssetPool and AssetPrice.
+
+// as read by Parser.parseAssetPrice in js/src/apricot.js, a bare u64 the SDKs don't scale
+#[repr(C, packed)]
+#[derive(Copy, Clone, Debug)]
+pub struct AssetPrice {
+    pub price_in_usd: u64,
+}
+
+unsafe impl Pod for AssetPrice {}
+
+impl AssetPrice {
+    pub fn try_from_account_info<'a>(
+        acc_info: &'a AccountInfo,
+    ) -> Result<Ref<'a, Self>, ApricotDecodeError> {
+        utils::check_owner(acc_info, &consts::program::ID)?;
+        utils::borrow_account::<Self>(acc_info)
+    }
+
+    pub fn try_from_bytes(data: &[u8]) -> Result<&Self, ApricotDecodeError> {
+        utils::try_cast::<Self>(data)
+    }
+}
+
+// wallets registered on a page, empty slots hold the default pubkey
+#[derive(Copy, Clone, Debug)]
+pub struct UsersPage<'a> {
+    slots: &'a [Pubkey],
+}
+
+impl<'a> UsersPage<'a> {
+    pub fn try_from_bytes(data: &'a [u8]) -> Result<Self, ApricotDecodeError> {
+        Ok(UsersPage {
+            slots: utils::try_cast_slice::<Pubkey>(data)?,
+        })
+    }
+
+    pub fn slots(&self) -> &'a [Pubkey] {
+        self.slots
+    }
+
+    pub fn wallets(&self) -> impl Iterator<Item = &'a Pubkey> {
+        self.slots.iter().filter(|k| **k != Pubkey::default())
+    }
+
+    pub fn user_info_keys(&self) -> impl Iterator<Item = Pubkey> + 'a {
+        self.wallets().map(consts::get_user_info_k)
+    }
+
+    pub fn contains(&self, wallet: &Pubkey) -> bool {
+        self.slots.contains(wallet)
+    }
+}
+
 #[cfg(test)]
 pub mod asset_pool_test {
     use super::*;
@@ -350,3 +518,91 @@ pub mod asset_pool_test {
         );
     }
 }
+
+#[cfg(test)]
+pub mod user_info_test {
+    use super::*;
+
+    #[test]
+    fn test_user_info_try_from_bytes() {
+        let size = std::mem::size_of::<UserInfo>();
+        let mut data = vec![0u8; size];
+        assert_eq!(
+            UserInfo::try_from_bytes(&data[..size - 1]).err(),
+            Some(ApricotDecodeError::TooShort {
+                expected: size,
+                actual: size - 1
+            })
+        );
+
+        data[2] = 3;
+        assert_eq!(UserInfo::try_from_bytes(&data).unwrap().num_assets, 3);
+
+        data[2] = MAX_ASSETS_PER_USER as u8 + 1;
+        assert_eq!(
+            UserInfo::try_from_bytes(&data).err(),
+            Some(ApricotDecodeError::InvalidField {
+                field: "num_assets",
+                value: MAX_ASSETS_PER_USER as u64 + 1
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod pool_list_test {
+    use super::*;
+
+    #[test]
+    fn test_pool_list_try_from_bytes() {
+        let mints = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut data = vec![0u8; 8 + 3 * 32];
+        data[0] = 2;
+        data[8..40].copy_from_slice(mints[0].as_ref());
+        data[40..72].copy_from_slice(mints[1].as_ref());
+
+        let pool_list = PoolList::try_from_bytes(&data).unwrap();
+        assert_eq!(pool_list.mint_keys(), &mints[..]);
+        assert_eq!(pool_list.pool_id_of(&mints[1]), Some(1));
+        assert_eq!(pool_list.get(2), None);
+
+        // a users page is a bare array of wallets
+        let page = UsersPage::try_from_bytes(&data[8..]).unwrap();
+        assert_eq!(page.slots().len(), 3);
+        assert_eq!(page.wallets().copied().collect::<Vec<_>>(), mints);
+        assert_eq!(
+            page.user_info_keys().next(),
+            Some(consts::get_user_info_k(&mints[0]))
+        );
+
+        data[0] = 4;
+        assert_eq!(
+            PoolList::try_from_bytes(&data).err(),
+            Some(ApricotDecodeError::TooShort {
+                expected: 8 + 4 * 32,
+                actual: 8 + 3 * 32
+            })
+        );
+    }
+}
+
+#[cfg(test)]
+pub mod asset_price_test {
+    use super::*;
+
+    #[test]
+    fn test_asset_price_try_from_bytes() {
+        let data = 123_456_789u64.to_le_bytes();
+        assert_eq!(
+            { AssetPrice::try_from_bytes(&data).unwrap().price_in_usd },
+            123_456_789
+        );
+        assert_eq!(
+            AssetPrice::try_from_bytes(&data[..4]).err(),
+            Some(ApricotDecodeError::TooShort {
+                expected: 8,
+                actual: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,409 @@
+use futures_util::future::BoxFuture;
+use futures_util::stream::{BoxStream, Stream, StreamExt};
+use solana_account_decoder::{UiAccount, UiAccountEncoding};
+use solana_program::pubkey::Pubkey;
+use solana_pubsub_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_rpc_client_api::{config::RpcAccountInfoConfig, response::Response};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::fmt;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::{Context, Poll};
+
+use crate::env::ApricotEnv;
+use crate::error::ApricotDecodeError;
+use crate::state::{AssetPool, UserInfo};
+
+// Pushed account updates over the PubSub websocket, for services that would otherwise poll
+// UserInfo and AssetPool accounts. mock::MockPubsub stands in for the PubSub node in tests, it is
+// built for this crate's tests and with the test-utils feature.
+
+#[derive(Debug)]
+pub enum SubscriptionError {
+    Pubsub(Box<PubsubClientError>),
+    // notification data the client cannot read, the node ignored the base64 encoding asked for
+    Encoding(Pubkey),
+    Decode {
+        key: Pubkey,
+        err: ApricotDecodeError,
+    },
+}
+
+impl fmt::Display for SubscriptionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SubscriptionError::Pubsub(err) => write!(f, "PubSub request failed: {}", err),
+            SubscriptionError::Encoding(key) => {
+                write!(f, "update of {} is not base64 encoded", key)
+            }
+            SubscriptionError::Decode { key, err } => {
+                write!(f, "cannot decode account {}: {}", key, err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SubscriptionError {}
+
+impl From<PubsubClientError> for SubscriptionError {
+    fn from(err: PubsubClientError) -> Self {
+        SubscriptionError::Pubsub(Box::new(err))
+    }
+}
+
+pub type SubscriptionResult<T> = Result<T, SubscriptionError>;
+
+// account state as of slot
+#[derive(Clone, Debug)]
+pub struct AccountUpdate<T> {
+    pub slot: u64,
+    pub key: Pubkey,
+    pub account: T,
+}
+
+pub struct ApricotSubscriber {
+    pubsub: PubsubClient,
+    env: ApricotEnv,
+    commitment: CommitmentConfig,
+}
+
+impl ApricotSubscriber {
+    // connects to env's cluster, updates are sent once confirmed
+    pub async fn connect(env: ApricotEnv) -> SubscriptionResult<Self> {
+        let url = env.cluster.ws_url();
+        ApricotSubscriber::connect_url(&url, env).await
+    }
+
+    pub async fn connect_url(url: &str, env: ApricotEnv) -> SubscriptionResult<Self> {
+        Ok(ApricotSubscriber {
+            pubsub: PubsubClient::new(url).await?,
+            env,
+            commitment: CommitmentConfig::confirmed(),
+        })
+    }
+
+    // applies to subscriptions made afterwards
+    pub fn with_commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    pub fn env(&self) -> &ApricotEnv {
+        &self.env
+    }
+
+    pub async fn user_info_updates(
+        &self,
+        wallet: &Pubkey,
+    ) -> SubscriptionResult<Subscription<'_, UserInfo>> {
+        self.account_updates(&self.env.user_info_k(wallet), |data| {
+            UserInfo::try_from_bytes(data).copied()
+        })
+        .await
+    }
+
+    pub async fn asset_pool_updates(
+        &self,
+        pool_id: u8,
+    ) -> SubscriptionResult<Subscription<'_, AssetPool>> {
+        self.account_updates(&self.env.asset_pool_k(pool_id), |data| {
+            AssetPool::try_from_bytes(data).copied()
+        })
+        .await
+    }
+
+    // updates of any program account, read with decode
+    pub async fn account_updates<T: Send + 'static>(
+        &self,
+        key: &Pubkey,
+        decode: fn(&[u8]) -> Result<T, ApricotDecodeError>,
+    ) -> SubscriptionResult<Subscription<'_, T>> {
+        let config = RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(self.commitment),
+            ..RpcAccountInfoConfig::default()
+        };
+        let (notifications, unsubscribe) = self.pubsub.account_subscribe(key, Some(config)).await?;
+        let key = *key;
+        let program_id = self.env.program_id();
+        let updates = notifications
+            .map(move |response| decode_update(&key, &program_id, response, decode))
+            .boxed();
+        Ok(Subscription {
+            key,
+            updates,
+            unsubscribe,
+        })
+    }
+
+    // closes the websocket, every Subscription must be dropped or unsubscribed first
+    pub async fn shutdown(self) -> SubscriptionResult<()> {
+        Ok(self.pubsub.shutdown().await?)
+    }
+}
+
+fn decode_update<T>(
+    key: &Pubkey,
+    program_id: &Pubkey,
+    response: Response<UiAccount>,
+    decode: fn(&[u8]) -> Result<T, ApricotDecodeError>,
+) -> SubscriptionResult<AccountUpdate<T>> {
+    let decode_err = |err| SubscriptionError::Decode { key: *key, err };
+    let owner =
+        Pubkey::from_str(&response.value.owner).map_err(|_| SubscriptionError::Encoding(*key))?;
+    // a closed account is reported as an empty system account
+    if owner != *program_id {
+        return Err(decode_err(ApricotDecodeError::WrongOwner {
+            expected: *program_id,
+            actual: owner,
+        }));
+    }
+    let data = response
+        .value
+        .data
+        .decode()
+        .ok_or(SubscriptionError::Encoding(*key))?;
+    Ok(AccountUpdate {
+        slot: response.context.slot,
+        key: *key,
+        account: decode(&data).map_err(decode_err)?,
+    })
+}
+
+// Stream of decoded updates of one account. Dropping it stops the updates locally, unsubscribe
+// also tells the node to stop sending them.
+pub struct Subscription<'a, T> {
+    key: Pubkey,
+    updates: BoxStream<'a, SubscriptionResult<AccountUpdate<T>>>,
+    unsubscribe: Box<dyn FnOnce() -> BoxFuture<'static, ()> + Send>,
+}
+
+impl<'a, T> Subscription<'a, T> {
+    pub fn key(&self) -> &Pubkey {
+        &self.key
+    }
+
+    pub async fn unsubscribe(self) {
+        (self.unsubscribe)().await
+    }
+}
+
+impl<'a, T> Stream for Subscription<'a, T> {
+    type Item = SubscriptionResult<AccountUpdate<T>>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.updates.poll_next_unpin(cx)
+    }
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod mock {
+    use base64::Engine;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use solana_program::pubkey::Pubkey;
+    use std::io;
+    use std::str::FromStr;
+    use std::sync::{Arc, Mutex};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio::sync::mpsc::{self, UnboundedSender};
+    use tokio_tungstenite::tungstenite::Message;
+
+    // Local PubSub server answering accountSubscribe and accountUnsubscribe. Updates are pushed
+    // with notify, to every connection subscribed to the account.
+    #[derive(Clone)]
+    pub struct MockPubsub {
+        url: String,
+        state: Arc<Mutex<MockState>>,
+    }
+
+    #[derive(Default)]
+    struct MockState {
+        next_subscription: u64,
+        subscriptions: Vec<MockSubscription>,
+    }
+
+    struct MockSubscription {
+        id: u64,
+        key: Pubkey,
+        sender: UnboundedSender<Message>,
+    }
+
+    impl MockPubsub {
+        // listens on a free local port until the runtime shuts down
+        pub async fn start() -> io::Result<Self> {
+            let listener = TcpListener::bind("127.0.0.1:0").await?;
+            let mock = MockPubsub {
+                url: format!("ws://{}", listener.local_addr()?),
+                state: Arc::default(),
+            };
+            let server = mock.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(server.clone().serve(stream));
+                }
+            });
+            Ok(mock)
+        }
+
+        pub fn url(&self) -> &str {
+            &self.url
+        }
+
+        // accounts with an open subscription, once per subscription
+        pub fn subscriptions(&self) -> Vec<Pubkey> {
+            let mut state = self.state.lock().unwrap();
+            state.subscriptions.retain(|sub| !sub.sender.is_closed());
+            state.subscriptions.iter().map(|sub| sub.key).collect()
+        }
+
+        // sends key's new state to its subscribers
+        pub fn notify(&self, key: &Pubkey, slot: u64, owner: &Pubkey, data: &[u8]) {
+            let account = json!({
+                "lamports": 1_000_000,
+                "data": [base64::engine::general_purpose::STANDARD.encode(data), "base64"],
+                "owner": owner.to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+                "space": data.len(),
+            });
+            let state = self.state.lock().unwrap();
+            for sub in state.subscriptions.iter().filter(|sub| sub.key == *key) {
+                let notification = json!({
+                    "jsonrpc": "2.0",
+                    "method": "accountNotification",
+                    "params": {
+                        "result": { "context": { "slot": slot }, "value": account },
+                        "subscription": sub.id,
+                    },
+                });
+                let _ = sub.sender.send(Message::Text(notification.to_string()));
+            }
+        }
+
+        async fn serve(self, stream: TcpStream) {
+            let ws = match tokio_tungstenite::accept_async(stream).await {
+                Ok(ws) => ws,
+                Err(_) => return,
+            };
+            let (mut sink, mut requests) = ws.split();
+            let (sender, mut outgoing) = mpsc::unbounded_channel();
+            tokio::spawn(async move {
+                while let Some(message) = outgoing.recv().await {
+                    if sink.send(message).await.is_err() {
+                        break;
+                    }
+                }
+            });
+            while let Some(Ok(message)) = requests.next().await {
+                let request: Value = match message {
+                    Message::Text(text) => match serde_json::from_str(&text) {
+                        Ok(request) => request,
+                        Err(_) => continue,
+                    },
+                    Message::Close(_) => break,
+                    _ => continue,
+                };
+                let response = self.respond(&request, &sender);
+                let _ = sender.send(Message::Text(response.to_string()));
+            }
+        }
+
+        fn respond(&self, request: &Value, sender: &UnboundedSender<Message>) -> Value {
+            let id = request["id"].clone();
+            let params = &request["params"];
+            let mut state = self.state.lock().unwrap();
+            let result = match request["method"].as_str() {
+                Some("accountSubscribe") => {
+                    match params[0]
+                        .as_str()
+                        .and_then(|key| Pubkey::from_str(key).ok())
+                    {
+                        Some(key) => {
+                            state.next_subscription += 1;
+                            let id = state.next_subscription;
+                            state.subscriptions.push(MockSubscription {
+                                id,
+                                key,
+                                sender: sender.clone(),
+                            });
+                            json!(id)
+                        }
+                        None => return error(id, -32602, "Invalid params"),
+                    }
+                }
+                Some("accountUnsubscribe") => {
+                    let before = state.subscriptions.len();
+                    state
+                        .subscriptions
+                        .retain(|sub| Some(sub.id) != params[0].as_u64());
+                    json!(state.subscriptions.len() < before)
+                }
+                _ => return error(id, -32601, "Method not found"),
+            };
+            json!({ "jsonrpc": "2.0", "result": result, "id": id })
+        }
+    }
+
+    fn error(id: Value, code: i64, message: &str) -> Value {
+        json!({ "jsonrpc": "2.0", "error": { "code": code, "message": message }, "id": id })
+    }
+}
+
+#[cfg(test)]
+pub mod subscription_test {
+    use super::mock::MockPubsub;
+    use super::*;
+    use crate::consts;
+    use crate::utils;
+
+    #[tokio::test]
+    async fn test_account_updates() {
+        let mock = MockPubsub::start().await.unwrap();
+        let subscriber = ApricotSubscriber::connect_url(mock.url(), ApricotEnv::mainnet())
+            .await
+            .unwrap();
+        let wallet = Pubkey::new_unique();
+        let mut user_updates = subscriber.user_info_updates(&wallet).await.unwrap();
+        let mut pool_updates = subscriber.asset_pool_updates(3).await.unwrap();
+        assert_eq!(
+            mock.subscriptions(),
+            vec![
+                consts::get_user_info_k(&wallet),
+                consts::get_asset_pool_k(3)
+            ]
+        );
+
+        let mut pool = vec![0u8; std::mem::size_of::<AssetPool>()];
+        utils::try_cast_mut::<AssetPool>(&mut pool).unwrap().pool_id = 3;
+        mock.notify(
+            &consts::get_asset_pool_k(3),
+            10,
+            &consts::program::ID,
+            &pool,
+        );
+        let update = pool_updates.next().await.unwrap().unwrap();
+        assert_eq!(update.slot, 10);
+        assert_eq!(update.key, consts::get_asset_pool_k(3));
+        assert_eq!(update.account.pool_id, 3);
+
+        let mut user_info = vec![0u8; std::mem::size_of::<UserInfo>()];
+        utils::try_cast_mut::<UserInfo>(&mut user_info)
+            .unwrap()
+            .num_assets = 1;
+        let user_info_k = consts::get_user_info_k(&wallet);
+        mock.notify(&user_info_k, 11, &consts::program::ID, &user_info);
+        let update = user_updates.next().await.unwrap().unwrap();
+        assert_eq!((update.slot, update.account.num_assets), (11, 1));
+
+        // closed accounts come back as system accounts
+        mock.notify(&user_info_k, 12, &Pubkey::default(), &[]);
+        match user_updates.next().await {
+            Some(Err(SubscriptionError::Decode { key, .. })) => assert_eq!(key, user_info_k),
+            other => panic!("unexpected {:?}", other.map(|u| u.map(|u| u.slot))),
+        }
+
+        user_updates.unsubscribe().await;
+        assert_eq!(mock.subscriptions(), vec![consts::get_asset_pool_k(3)]);
+    }
+}
@@ -0,0 +1,227 @@
+use solana_program::{instruction::AccountMeta, pubkey::Pubkey, sysvar};
+
+use crate::consts;
+use crate::instructions::SwapTarget;
+
+// Accounts of the DEX a margin swap goes through. TxMaker.margin_swap in js/src/apricot.js appends
+// them untouched after the accounts common to every margin swap, so each list below is the DEX
+// program followed by the accounts of that DEX's own swap instruction, in its order, minus the
+// owner, source, destination and token program the common accounts already carry (base_pda, the
+// two asset pool SPL accounts and the token program).
+
+// a serum market order settled right away, the open orders account belongs to base_pda
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SerumSwapAccounts {
+    pub dex_program: Pubkey,
+    pub market: Pubkey,
+    pub open_orders: Pubkey,
+    pub request_queue: Pubkey,
+    pub event_queue: Pubkey,
+    pub bids: Pubkey,
+    pub asks: Pubkey,
+    pub coin_vault: Pubkey,
+    pub pc_vault: Pubkey,
+    pub vault_signer: Pubkey,
+}
+
+impl SerumSwapAccounts {
+    // new_order_v3 then settle_funds
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(self.dex_program, false),
+            AccountMeta::new(self.market, false),
+            AccountMeta::new(self.open_orders, false),
+            AccountMeta::new(self.request_queue, false),
+            AccountMeta::new(self.event_queue, false),
+            AccountMeta::new(self.bids, false),
+            AccountMeta::new(self.asks, false),
+            AccountMeta::new(self.coin_vault, false),
+            AccountMeta::new(self.pc_vault, false),
+            AccountMeta::new_readonly(self.vault_signer, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RaydiumSwapAccounts {
+    pub amm_id: Pubkey,
+    pub amm_authority: Pubkey,
+    pub amm_open_orders: Pubkey,
+    pub amm_target_orders: Pubkey,
+    pub pool_coin_token: Pubkey,
+    pub pool_pc_token: Pubkey,
+    pub serum_program: Pubkey,
+    pub serum_market: Pubkey,
+    pub serum_bids: Pubkey,
+    pub serum_asks: Pubkey,
+    pub serum_event_queue: Pubkey,
+    pub serum_coin_vault: Pubkey,
+    pub serum_pc_vault: Pubkey,
+    pub serum_vault_signer: Pubkey,
+}
+
+impl RaydiumSwapAccounts {
+    // amm swap_base_in
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::raydium_amm_program::ID, false),
+            AccountMeta::new(self.amm_id, false),
+            AccountMeta::new_readonly(self.amm_authority, false),
+            AccountMeta::new(self.amm_open_orders, false),
+            AccountMeta::new(self.amm_target_orders, false),
+            AccountMeta::new(self.pool_coin_token, false),
+            AccountMeta::new(self.pool_pc_token, false),
+            AccountMeta::new_readonly(self.serum_program, false),
+            AccountMeta::new(self.serum_market, false),
+            AccountMeta::new(self.serum_bids, false),
+            AccountMeta::new(self.serum_asks, false),
+            AccountMeta::new(self.serum_event_queue, false),
+            AccountMeta::new(self.serum_coin_vault, false),
+            AccountMeta::new(self.serum_pc_vault, false),
+            AccountMeta::new_readonly(self.serum_vault_signer, false),
+        ]
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SaberSwapAccounts {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    // swap vaults of the sold and the bought token
+    pub source_vault: Pubkey,
+    pub destination_vault: Pubkey,
+    // admin fee account of the bought token
+    pub admin_fee_destination: Pubkey,
+}
+
+impl SaberSwapAccounts {
+    // stable-swap swap
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::saber_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.source_vault, false),
+            AccountMeta::new(self.destination_vault, false),
+            AccountMeta::new(self.admin_fee_destination, false),
+            AccountMeta::new_readonly(sysvar::clock::ID, false),
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MercurialSwapAccounts {
+    pub swap: Pubkey,
+    pub pool_authority: Pubkey,
+    // every token account of the swap, in the swap's order
+    pub token_accounts: Vec<Pubkey>,
+}
+
+impl MercurialSwapAccounts {
+    // stable-swap exchange
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        let mut keys = vec![
+            AccountMeta::new_readonly(consts::mercurial_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.pool_authority, false),
+        ];
+        keys.extend(
+            self.token_accounts
+                .iter()
+                .map(|token_account| AccountMeta::new(*token_account, false)),
+        );
+        keys
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct OrcaSwapAccounts {
+    pub swap: Pubkey,
+    pub swap_authority: Pubkey,
+    // swap vaults of the sold and the bought token
+    pub source_vault: Pubkey,
+    pub destination_vault: Pubkey,
+    pub pool_mint: Pubkey,
+    pub fee_account: Pubkey,
+}
+
+impl OrcaSwapAccounts {
+    // token-swap swap
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        vec![
+            AccountMeta::new_readonly(consts::orca_swap_program::ID, false),
+            AccountMeta::new_readonly(self.swap, false),
+            AccountMeta::new_readonly(self.swap_authority, false),
+            AccountMeta::new(self.source_vault, false),
+            AccountMeta::new(self.destination_vault, false),
+            AccountMeta::new(self.pool_mint, false),
+            AccountMeta::new(self.fee_account, false),
+        ]
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SwapAccounts {
+    Serum(SerumSwapAccounts),
+    Raydium(RaydiumSwapAccounts),
+    Saber(SaberSwapAccounts),
+    Mercurial(MercurialSwapAccounts),
+    Orca(OrcaSwapAccounts),
+}
+
+impl SwapAccounts {
+    pub fn target_swap(&self) -> SwapTarget {
+        match self {
+            SwapAccounts::Serum(_) => SwapTarget::Serum,
+            SwapAccounts::Raydium(_) => SwapTarget::Raydium,
+            SwapAccounts::Saber(_) => SwapTarget::Saber,
+            SwapAccounts::Mercurial(_) => SwapTarget::Mercurial,
+            SwapAccounts::Orca(_) => SwapTarget::Orca,
+        }
+    }
+
+    // accounts appended to margin_swap
+    pub fn keys(&self) -> Vec<AccountMeta> {
+        match self {
+            SwapAccounts::Serum(accounts) => accounts.keys(),
+            SwapAccounts::Raydium(accounts) => accounts.keys(),
+            SwapAccounts::Saber(accounts) => accounts.keys(),
+            SwapAccounts::Mercurial(accounts) => accounts.keys(),
+            SwapAccounts::Orca(accounts) => accounts.keys(),
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod swap_test {
+    use super::*;
+
+    #[test]
+    fn test_swap_accounts() {
+        let mercurial = SwapAccounts::Mercurial(MercurialSwapAccounts {
+            swap: Pubkey::new_unique(),
+            pool_authority: Pubkey::new_unique(),
+            token_accounts: vec![Pubkey::new_unique(); 3],
+        });
+        assert_eq!(mercurial.target_swap(), SwapTarget::Mercurial);
+        let keys = mercurial.keys();
+        assert_eq!(keys.len(), 6);
+        assert_eq!(keys[0].pubkey, consts::mercurial_swap_program::ID);
+        assert!(keys[3..].iter().all(|key| key.is_writable));
+
+        let orca = OrcaSwapAccounts {
+            swap: Pubkey::new_unique(),
+            swap_authority: Pubkey::new_unique(),
+            source_vault: Pubkey::new_unique(),
+            destination_vault: Pubkey::new_unique(),
+            pool_mint: Pubkey::new_unique(),
+            fee_account: Pubkey::new_unique(),
+        };
+        let keys = SwapAccounts::Orca(orca).keys();
+        assert_eq!(keys[0].pubkey, consts::orca_swap_program::ID);
+        assert_eq!(keys[3], AccountMeta::new(orca.source_vault, false));
+        assert_eq!(keys[4], AccountMeta::new(orca.destination_vault, false));
+        assert!(!keys.iter().any(|key| key.is_signer));
+    }
+}
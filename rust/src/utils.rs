@@ -1,34 +1,266 @@
 use itertools::Itertools;
+use solana_program::{account_info::AccountInfo, pubkey::Pubkey};
+use std::cell::Ref;
 
-#[repr(packed)]
+use crate::error::ApricotDecodeError;
+
+/// Marker for structs that can be read straight out of account or instruction bytes.
+///
+/// # Safety
+/// Implementors must be `#[repr(C, packed)]`, so they have alignment 1 and no padding, and only
+/// hold fields for which every bit pattern is valid: integers, floats, `Pubkey`, and arrays or
+/// other `Pod` structs of those.
+pub unsafe trait Pod: Copy {}
+
+unsafe impl Pod for () {}
+unsafe impl Pod for Pubkey {}
+
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct UserInfoHeader {
     pub page_id: u16,
 }
 
-pub const NUM_PAGES:usize = 5000;
+unsafe impl Pod for UserInfoHeader {}
+
+pub const NUM_PAGES: usize = 5000;
 
-#[repr(packed)]
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
 pub struct UserPagesStats {
     pub num_free_slots: [u16; NUM_PAGES],
 }
 
-pub fn cast<T>(data: &[u8]) -> &T {
-    assert!(data.len() >= std::mem::size_of::<T>());
-    return unsafe{std::mem::transmute(data.as_ptr())};
+unsafe impl Pod for UserPagesStats {}
+
+// how to pick the page a new user registers on
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PageStrategy {
+    // page with the most free slots, ties go to the highest page_id
+    MostFree,
+    // one of the k pages with the most free slots, chosen by seed, so concurrent sign-ups
+    // don't all write to the same page
+    RandomTopK { k: usize, seed: u64 },
+    // lowest page_id with a free slot
+    FirstFit,
+}
+
+impl PageStrategy {
+    // RandomTopK seeded by the wallet, stable for a wallet but spread across wallets
+    pub fn spread_by_wallet(k: usize, wallet: &Pubkey) -> Self {
+        let mut seed_bytes = [0u8; 8];
+        seed_bytes.copy_from_slice(&wallet.as_ref()[..8]);
+        PageStrategy::RandomTopK {
+            k,
+            seed: u64::from_le_bytes(seed_bytes),
+        }
+    }
+}
+
+impl UserPagesStats {
+    pub fn try_from_bytes(data: &[u8]) -> Result<&Self, ApricotDecodeError> {
+        try_cast::<Self>(data)
+    }
+
+    pub fn free_slots(&self, page_id: u16) -> Option<u16> {
+        if (page_id as usize) < NUM_PAGES {
+            Some(self.free_slots_unchecked(page_id as usize))
+        } else {
+            None
+        }
+    }
+
+    // one element of the packed array, without copying the whole array out first
+    fn free_slots_unchecked(&self, i: usize) -> u16 {
+        let first = std::ptr::addr_of!(self.num_free_slots) as *const u16;
+        // i < NUM_PAGES, read_unaligned because packed fields have alignment 1
+        unsafe { first.add(i).read_unaligned() }
+    }
+
+    fn iter(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..NUM_PAGES).map(move |i| self.free_slots_unchecked(i))
+    }
+
+    pub fn total_free_slots(&self) -> u64 {
+        self.iter().map(|n| n as u64).sum()
+    }
+
+    // slots of every page, a UsersPage holds UsersPage::slots().len() wallets and the SDKs don't
+    // publish that count
+    pub fn total_capacity(&self, slots_per_page: u16) -> u64 {
+        NUM_PAGES as u64 * slots_per_page as u64
+    }
+
+    pub fn full_pages(&self) -> Vec<u16> {
+        self.pages_where(|n| n == 0)
+    }
+
+    pub fn pages_with_free_slots(&self) -> Vec<u16> {
+        self.pages_where(|n| n > 0)
+    }
+
+    fn pages_where(&self, f: impl Fn(u16) -> bool) -> Vec<u16> {
+        self.iter().positions(f).map(|i| i as u16).collect()
+    }
+
+    // None when every page is full
+    pub fn select_page(&self, strategy: PageStrategy) -> Option<u16> {
+        match strategy {
+            PageStrategy::MostFree => self
+                .iter()
+                .position_max()
+                .filter(|i| self.free_slots_unchecked(*i) > 0)
+                .map(|i| i as u16),
+            PageStrategy::RandomTopK { k, seed } => {
+                let mut candidates = self.pages_with_free_slots();
+                candidates
+                    .sort_by_key(|i| std::cmp::Reverse(self.free_slots_unchecked(*i as usize)));
+                candidates.truncate(k.max(1));
+                if candidates.is_empty() {
+                    None
+                } else {
+                    Some(candidates[(seed % candidates.len() as u64) as usize])
+                }
+            }
+            PageStrategy::FirstFit => self.iter().position(|n| n > 0).map(|i| i as u16),
+        }
+    }
+}
+
+// checked zero-copy view of data as T
+pub fn try_cast<T: Pod>(data: &[u8]) -> Result<&T, ApricotDecodeError> {
+    check_layout::<T>(data)?;
+    // length and alignment are checked above and any bit pattern is a valid T
+    Ok(unsafe { &*(data.as_ptr() as *const T) })
+}
+
+pub fn try_cast_mut<T: Pod>(data: &mut [u8]) -> Result<&mut T, ApricotDecodeError> {
+    check_layout::<T>(data)?;
+    Ok(unsafe { &mut *(data.as_mut_ptr() as *mut T) })
+}
+
+fn check_layout<T>(data: &[u8]) -> Result<(), ApricotDecodeError> {
+    let expected = std::mem::size_of::<T>();
+    if data.len() < expected {
+        return Err(ApricotDecodeError::TooShort {
+            expected,
+            actual: data.len(),
+        });
+    }
+    if data.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+        return Err(ApricotDecodeError::Misaligned);
+    }
+    Ok(())
+}
+
+// checked zero-copy view of data as a slice of T, trailing bytes that don't fill a T are ignored
+pub fn try_cast_slice<T: Pod>(data: &[u8]) -> Result<&[T], ApricotDecodeError> {
+    if data.as_ptr().align_offset(std::mem::align_of::<T>()) != 0 {
+        return Err(ApricotDecodeError::Misaligned);
+    }
+    let len = data.len() / std::mem::size_of::<T>();
+    Ok(unsafe { std::slice::from_raw_parts(data.as_ptr() as *const T, len) })
+}
+
+// panics where try_cast fails, only use on data known to be well formed
+pub fn cast<T: Pod>(data: &[u8]) -> &T {
+    match try_cast::<T>(data) {
+        Ok(t) => t,
+        Err(err) => panic!("{}", err),
+    }
 }
 
-pub const INVALID_PAGE_ID:u16 = u16::MAX;
+// borrows acc_info's data as T without checking who owns it
+pub fn borrow_account<'a, T: Pod>(
+    acc_info: &'a AccountInfo,
+) -> Result<Ref<'a, T>, ApricotDecodeError> {
+    let data_ref = acc_info
+        .try_borrow_data()
+        .map_err(ApricotDecodeError::AccountBorrow)?;
+    try_cast::<T>(&data_ref)?;
+    Ok(Ref::map(data_ref, |d| cast::<T>(d)))
+}
 
-pub fn is_user_active(data:&[u8]) -> bool {
+pub fn check_owner(acc_info: &AccountInfo, owner: &Pubkey) -> Result<(), ApricotDecodeError> {
+    if acc_info.owner != owner {
+        return Err(ApricotDecodeError::WrongOwner {
+            expected: *owner,
+            actual: *acc_info.owner,
+        });
+    }
+    Ok(())
+}
+
+pub const INVALID_PAGE_ID: u16 = u16::MAX;
+
+#[deprecated(note = "panics on short data, use UserInfo::is_active")]
+pub fn is_user_active(data: &[u8]) -> bool {
     let user_info_header = cast::<UserInfoHeader>(data);
-    return user_info_header.page_id != INVALID_PAGE_ID;
+    user_info_header.page_id != INVALID_PAGE_ID
 }
 
-pub fn get_best_page_id(data:&[u8]) -> u16 {
+#[deprecated(note = "panics on short data and picks full pages, use UserPagesStats::select_page")]
+pub fn get_best_page_id(data: &[u8]) -> u16 {
     let user_pages_stats = cast::<UserPagesStats>(data);
-    #[allow(unaligned_references)] {
-        let max_page_id = user_pages_stats.num_free_slots.iter().position_max().unwrap();
-        assert!(max_page_id < NUM_PAGES);
-        return max_page_id as u16;
+    let max_page_id = { user_pages_stats.num_free_slots }
+        .iter()
+        .position_max()
+        .unwrap();
+    assert!(max_page_id < NUM_PAGES);
+    max_page_id as u16
+}
+
+#[cfg(test)]
+pub mod utils_test {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_try_cast_checks_length() {
+        let data = vec![0u8; 2 * NUM_PAGES - 1];
+        assert_eq!(
+            UserPagesStats::try_from_bytes(&data).err(),
+            Some(ApricotDecodeError::TooShort {
+                expected: 2 * NUM_PAGES,
+                actual: 2 * NUM_PAGES - 1
+            })
+        );
+
+        let mut data = vec![0u8; 2 * NUM_PAGES + 1];
+        data[2 * 7] = 3;
+        // packed structs can be read at any offset
+        let stats = UserPagesStats::try_from_bytes(&data[1..]).unwrap();
+        assert_eq!({ stats.num_free_slots }[6], 3 << 8);
+        assert_eq!(stats.free_slots(6), Some(3 << 8));
+        assert_eq!(stats.free_slots(NUM_PAGES as u16), None);
+        assert_eq!(get_best_page_id(&data[..2 * NUM_PAGES]), 7);
+    }
+
+    #[test]
+    fn test_select_page() {
+        let mut data = vec![0u8; 2 * NUM_PAGES];
+        let stats = UserPagesStats::try_from_bytes(&data).unwrap();
+        assert_eq!(stats.select_page(PageStrategy::MostFree), None);
+        assert_eq!(stats.full_pages().len(), NUM_PAGES);
+
+        data[2 * 3] = 5;
+        data[2 * 9] = 8;
+        data[2 * 20] = 8;
+        data[2 * 40] = 1;
+        let stats = UserPagesStats::try_from_bytes(&data).unwrap();
+        assert_eq!(stats.total_free_slots(), 22);
+        assert_eq!(stats.total_capacity(10), 10 * NUM_PAGES as u64);
+        assert_eq!(stats.pages_with_free_slots(), vec![3, 9, 20, 40]);
+        assert_eq!(stats.select_page(PageStrategy::MostFree), Some(20));
+        assert_eq!(stats.select_page(PageStrategy::FirstFit), Some(3));
+
+        let picked = (0..6)
+            .map(|seed| {
+                stats
+                    .select_page(PageStrategy::RandomTopK { k: 3, seed })
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(picked, vec![9, 20, 3, 9, 20, 3]);
     }
 }
@@ -0,0 +1,490 @@
+use solana_program::pubkey::Pubkey;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::state::{AssetPool, UserAssetInfo, UserInfo};
+
+// Owned copies of the on-chain accounts in native units, with accessors converting to UI units.
+// Unlike state, these do not change with the account layout.
+
+// rates on chain are APR, interest accrues continuously
+pub fn apr_to_apy(apr: f64) -> f64 {
+    apr.exp_m1()
+}
+
+fn to_system_time(timestamp: u64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(timestamp)
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AssetPoolView {
+    token_name: String,
+    mint: Pubkey,
+    pool_id: u8,
+    decimal_multiplier: u64,
+
+    deposit_amount: u64,
+    deposit_index: f64,
+    borrow_amount: u64,
+    borrow_index: f64,
+
+    reserve_factor: f64,
+    fee_amount: u64,
+    deposit_rate: f64,
+    borrow_rate: f64,
+    last_update_time: u64,
+
+    spl: Pubkey,
+    atoken_mint: Pubkey,
+    asset_price: Pubkey,
+    pyth_price: Pubkey,
+
+    ltv: f64,
+    safe_factor: f64,
+
+    base_rate: f64,
+    multiplier: f64,
+    jump_multiplier: f64,
+    kink: f64,
+
+    deposit_apt_reward_per_year: u64,
+    borrow_apt_reward_per_year: u64,
+    reward_deposit_index: f64,
+    reward_borrow_index: f64,
+
+    deposit_cap: u64,
+    is_disabled: bool,
+    farm_yield: f64,
+}
+
+impl AssetPoolView {
+    // decimals of the pool's mint as in PoolConfig::decimals, AssetPool::mint_decimal_multiplier
+    // is not 10^decimals
+    pub fn new(pool: &AssetPool, decimals: u8) -> Self {
+        let token_name = { pool.token_name };
+        let name_len = token_name
+            .iter()
+            .position(|b| *b == 0)
+            .unwrap_or(token_name.len());
+        AssetPoolView {
+            token_name: String::from_utf8_lossy(&token_name[..name_len]).into_owned(),
+            mint: pool.mint_key,
+            pool_id: pool.pool_id,
+            decimal_multiplier: 10u64.pow(decimals as u32),
+            deposit_amount: { pool.deposit_amount }.to_native_amount(),
+            deposit_index: pool.deposit_index,
+            borrow_amount: { pool.borrow_amount }.to_native_amount(),
+            borrow_index: pool.borrow_index,
+            reserve_factor: pool.reserve_factor,
+            fee_amount: { pool.fee_amount }.to_native_amount(),
+            deposit_rate: pool.current_deposit_rate,
+            borrow_rate: pool.current_borrow_rate,
+            last_update_time: pool.last_update_time,
+            spl: pool.spl_key,
+            atoken_mint: pool.atoken_mint_key,
+            asset_price: pool.asset_price_key,
+            pyth_price: pool.pyth_price_key,
+            ltv: pool.ltv,
+            safe_factor: pool.safe_factor,
+            base_rate: pool.base_rate,
+            multiplier: pool.multiplier,
+            jump_multiplier: pool.jump_multiplier,
+            kink: pool.kink,
+            deposit_apt_reward_per_year: pool.deposit_apt_reward_amount_per_year,
+            borrow_apt_reward_per_year: pool.borrow_apt_reward_amount_per_year,
+            reward_deposit_index: pool.reward_deposit_index,
+            reward_borrow_index: pool.reward_borrow_index,
+            deposit_cap: pool.deposit_cap,
+            is_disabled: pool.is_disabled != 0,
+            farm_yield: pool.farm_yield,
+        }
+    }
+
+    pub fn token_name(&self) -> &str {
+        &self.token_name
+    }
+
+    pub fn mint(&self) -> &Pubkey {
+        &self.mint
+    }
+
+    pub fn pool_id(&self) -> u8 {
+        self.pool_id
+    }
+
+    // 10^decimals of the mint
+    pub fn decimal_multiplier(&self) -> u64 {
+        self.decimal_multiplier
+    }
+
+    pub fn to_ui_amount(&self, native_amount: u64) -> f64 {
+        native_amount as f64 / self.decimal_multiplier as f64
+    }
+
+    // rounded to the nearest native unit, 0.29 USDC is 290_000 and not 289_999
+    pub fn to_native_amount(&self, ui_amount: f64) -> u64 {
+        (ui_amount * self.decimal_multiplier as f64).round() as u64
+    }
+
+    pub fn deposit_amount_native(&self) -> u64 {
+        self.deposit_amount
+    }
+
+    pub fn deposit_amount(&self) -> f64 {
+        self.to_ui_amount(self.deposit_amount)
+    }
+
+    pub fn borrow_amount_native(&self) -> u64 {
+        self.borrow_amount
+    }
+
+    pub fn borrow_amount(&self) -> f64 {
+        self.to_ui_amount(self.borrow_amount)
+    }
+
+    // what can still be borrowed or withdrawn from the pool
+    pub fn available_amount(&self) -> f64 {
+        self.to_ui_amount(self.deposit_amount.saturating_sub(self.borrow_amount))
+    }
+
+    pub fn fee_amount(&self) -> f64 {
+        self.to_ui_amount(self.fee_amount)
+    }
+
+    pub fn deposit_cap(&self) -> f64 {
+        self.to_ui_amount(self.deposit_cap)
+    }
+
+    pub fn deposit_index(&self) -> f64 {
+        self.deposit_index
+    }
+
+    pub fn borrow_index(&self) -> f64 {
+        self.borrow_index
+    }
+
+    pub fn utilization_rate(&self) -> f64 {
+        if self.deposit_amount == 0 {
+            0.0
+        } else {
+            self.borrow_amount as f64 / self.deposit_amount as f64
+        }
+    }
+
+    pub fn deposit_apr(&self) -> f64 {
+        self.deposit_rate
+    }
+
+    pub fn deposit_apy(&self) -> f64 {
+        apr_to_apy(self.deposit_rate)
+    }
+
+    pub fn borrow_apr(&self) -> f64 {
+        self.borrow_rate
+    }
+
+    pub fn borrow_apy(&self) -> f64 {
+        apr_to_apy(self.borrow_rate)
+    }
+
+    // yield of the farm LP token of this pool is staked into, APR
+    pub fn farm_yield(&self) -> f64 {
+        self.farm_yield
+    }
+
+    pub fn reserve_factor(&self) -> f64 {
+        self.reserve_factor
+    }
+
+    pub fn base_rate(&self) -> f64 {
+        self.base_rate
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    pub fn jump_multiplier(&self) -> f64 {
+        self.jump_multiplier
+    }
+
+    pub fn kink(&self) -> f64 {
+        self.kink
+    }
+
+    pub fn ltv(&self) -> f64 {
+        self.ltv
+    }
+
+    pub fn safe_factor(&self) -> f64 {
+        self.safe_factor
+    }
+
+    // APT rewards are in native APT units
+    pub fn deposit_apt_reward_per_year_native(&self) -> u64 {
+        self.deposit_apt_reward_per_year
+    }
+
+    pub fn borrow_apt_reward_per_year_native(&self) -> u64 {
+        self.borrow_apt_reward_per_year
+    }
+
+    pub fn reward_deposit_index(&self) -> f64 {
+        self.reward_deposit_index
+    }
+
+    pub fn reward_borrow_index(&self) -> f64 {
+        self.reward_borrow_index
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.is_disabled
+    }
+
+    pub fn last_update_time(&self) -> SystemTime {
+        to_system_time(self.last_update_time)
+    }
+
+    pub fn spl(&self) -> &Pubkey {
+        &self.spl
+    }
+
+    pub fn atoken_mint(&self) -> &Pubkey {
+        &self.atoken_mint
+    }
+
+    pub fn asset_price(&self) -> &Pubkey {
+        &self.asset_price
+    }
+
+    pub fn pyth_price(&self) -> &Pubkey {
+        &self.pyth_price
+    }
+}
+
+// user's position in a single pool. UserInfo does not know the decimals of the pool, so UI amounts
+// need the matching AssetPoolView.
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserAssetView {
+    pool_id: u8,
+    use_as_collateral: bool,
+
+    deposit_amount: u64,
+    deposit_interests: u64,
+    deposit_index: f64,
+    reward_deposit_amount: f64,
+    reward_deposit_index: f64,
+
+    borrow_amount: u64,
+    borrow_interests: u64,
+    borrow_index: f64,
+    reward_borrow_amount: f64,
+    reward_borrow_index: f64,
+}
+
+impl From<&UserAssetInfo> for UserAssetView {
+    fn from(info: &UserAssetInfo) -> Self {
+        UserAssetView {
+            pool_id: info.pool_id,
+            use_as_collateral: info.use_as_collateral != 0,
+            deposit_amount: { info.deposit_amount }.to_native_amount(),
+            deposit_interests: info.deposit_interests,
+            deposit_index: info.deposit_index,
+            reward_deposit_amount: info.reward_deposit_amount,
+            reward_deposit_index: info.reward_deposit_index,
+            borrow_amount: { info.borrow_amount }.to_native_amount(),
+            borrow_interests: info.borrow_interests,
+            borrow_index: info.borrow_index,
+            reward_borrow_amount: info.reward_borrow_amount,
+            reward_borrow_index: info.reward_borrow_index,
+        }
+    }
+}
+
+impl UserAssetView {
+    pub fn pool_id(&self) -> u8 {
+        self.pool_id
+    }
+
+    pub fn use_as_collateral(&self) -> bool {
+        self.use_as_collateral
+    }
+
+    pub fn deposit_amount_native(&self) -> u64 {
+        self.deposit_amount
+    }
+
+    pub fn deposit_amount(&self, pool: &AssetPoolView) -> f64 {
+        debug_assert_eq!(pool.pool_id, self.pool_id);
+        pool.to_ui_amount(self.deposit_amount)
+    }
+
+    pub fn deposit_interests(&self, pool: &AssetPoolView) -> f64 {
+        debug_assert_eq!(pool.pool_id, self.pool_id);
+        pool.to_ui_amount(self.deposit_interests)
+    }
+
+    pub fn borrow_amount_native(&self) -> u64 {
+        self.borrow_amount
+    }
+
+    pub fn borrow_amount(&self, pool: &AssetPoolView) -> f64 {
+        debug_assert_eq!(pool.pool_id, self.pool_id);
+        pool.to_ui_amount(self.borrow_amount)
+    }
+
+    pub fn borrow_interests(&self, pool: &AssetPoolView) -> f64 {
+        debug_assert_eq!(pool.pool_id, self.pool_id);
+        pool.to_ui_amount(self.borrow_interests)
+    }
+
+    pub fn deposit_index(&self) -> f64 {
+        self.deposit_index
+    }
+
+    pub fn borrow_index(&self) -> f64 {
+        self.borrow_index
+    }
+
+    // APT reward accrued so far, native APT units
+    pub fn reward_deposit_amount_native(&self) -> f64 {
+        self.reward_deposit_amount
+    }
+
+    pub fn reward_borrow_amount_native(&self) -> f64 {
+        self.reward_borrow_amount
+    }
+
+    pub fn reward_deposit_index(&self) -> f64 {
+        self.reward_deposit_index
+    }
+
+    pub fn reward_borrow_index(&self) -> f64 {
+        self.reward_borrow_index
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct UserInfoView {
+    page_id: u16,
+    is_active: bool,
+    assets: Vec<UserAssetView>,
+
+    vesting_apt: f64,
+    available_apt: f64,
+    last_vest_cutoff_timestamp: u64,
+    last_update_timestamp: u64,
+}
+
+impl From<&UserInfo> for UserInfoView {
+    fn from(user_info: &UserInfo) -> Self {
+        let user_asset_info = { user_info.user_asset_info };
+        let num_assets = (user_info.num_assets as usize).min(user_asset_info.len());
+        let reward = { user_info.reward };
+        UserInfoView {
+            page_id: user_info.page_id,
+            is_active: user_info.is_active(),
+            assets: user_asset_info[..num_assets]
+                .iter()
+                .map(UserAssetView::from)
+                .collect(),
+            vesting_apt: reward.vesting_apt,
+            available_apt: reward.available_apt,
+            last_vest_cutoff_timestamp: user_info.last_vest_cutoff_timestamp,
+            last_update_timestamp: user_info.last_update_timestamp,
+        }
+    }
+}
+
+impl UserInfoView {
+    // as UserInfo::is_active
+    pub fn is_active(&self) -> bool {
+        self.is_active
+    }
+
+    pub fn page_id(&self) -> u16 {
+        self.page_id
+    }
+
+    pub fn assets(&self) -> &[UserAssetView] {
+        &self.assets
+    }
+
+    pub fn asset(&self, pool_id: u8) -> Option<&UserAssetView> {
+        self.assets.iter().find(|asset| asset.pool_id == pool_id)
+    }
+
+    pub fn vesting_apt_native(&self) -> f64 {
+        self.vesting_apt
+    }
+
+    pub fn available_apt_native(&self) -> f64 {
+        self.available_apt
+    }
+
+    pub fn last_vest_cutoff_time(&self) -> SystemTime {
+        to_system_time(self.last_vest_cutoff_timestamp)
+    }
+
+    pub fn last_update_time(&self) -> SystemTime {
+        to_system_time(self.last_update_timestamp)
+    }
+}
+
+#[cfg(test)]
+pub mod view_test {
+    use super::*;
+    use crate::state::{RawAmt, NATIVE_RAW_SHIFT};
+    use crate::utils::{self, INVALID_PAGE_ID};
+
+    fn raw_amt(native_amount: u64) -> RawAmt {
+        let mut raw = [0u8; 16];
+        raw.copy_from_slice(&((native_amount as u128) << NATIVE_RAW_SHIFT).to_le_bytes());
+        *utils::cast::<RawAmt>(&raw)
+    }
+
+    #[test]
+    fn test_asset_pool_view() {
+        let mut data = vec![0u8; std::mem::size_of::<AssetPool>()];
+        let pool = utils::try_cast_mut::<AssetPool>(&mut data).unwrap();
+        pool.token_name[..4].copy_from_slice(b"USDC");
+        pool.pool_id = 3;
+        // 9 - decimals, not what UI amounts are derived from
+        pool.mint_decimal_multiplier = 3;
+        pool.deposit_amount = raw_amt(5_000_000);
+        pool.borrow_amount = raw_amt(2_000_000);
+        pool.current_borrow_rate = 0.1;
+        pool.last_update_time = 1_640_000_000;
+
+        let view = AssetPoolView::new(AssetPool::from_bytes(&data), 6);
+        assert_eq!(view.token_name(), "USDC");
+        assert_eq!(view.decimal_multiplier(), 1_000_000);
+        assert_eq!(view.to_native_amount(0.29), 290_000);
+        assert_eq!(view.deposit_amount(), 5.0);
+        assert_eq!(view.available_amount(), 3.0);
+        assert_eq!(view.utilization_rate(), 0.4);
+        assert!((view.borrow_apy() - 0.105170918).abs() < 1e-9);
+        assert_eq!(
+            view.last_update_time(),
+            UNIX_EPOCH + Duration::from_secs(1_640_000_000)
+        );
+    }
+
+    #[test]
+    fn test_user_info_view() {
+        let mut data = vec![0u8; std::mem::size_of::<UserInfo>()];
+        let user_info = utils::try_cast_mut::<UserInfo>(&mut data).unwrap();
+        user_info.page_id = INVALID_PAGE_ID;
+        user_info.num_assets = 1;
+        user_info.user_asset_info[0].pool_id = 3;
+        user_info.user_asset_info[0].use_as_collateral = 1;
+        user_info.user_asset_info[0].deposit_amount = raw_amt(1_500_000);
+
+        let view = UserInfoView::from(UserInfo::from_bytes(&data));
+        assert!(!view.is_active());
+        assert_eq!(view.assets().len(), 1);
+        let asset = view.asset(3).unwrap();
+        assert!(asset.use_as_collateral());
+        assert_eq!(asset.deposit_amount_native(), 1_500_000);
+        assert!(view.asset(4).is_none());
+    }
+}